@@ -0,0 +1,239 @@
+use crate::*;
+
+// compositing and blend modes on (r, g, b, a) colors, following the w3c
+// compositing and blending spec. straight alpha unless the name says premul
+
+impl Vec4 {
+    /// rgb multiplied by alpha
+    pub fn premultiply(&self) -> Vec4 { vec4(self.x * self.w, self.y * self.w, self.z * self.w, self.w) }
+    /// rgb divided by alpha, fully transparent gives zero
+    pub fn unpremultiply(&self) -> Vec4 {
+        if self.w == 0.0 { return Vec4::default(); }
+        vec4(self.x / self.w, self.y / self.w, self.z / self.w, self.w)
+    }
+    /// self composited over dst, straight alpha
+    pub fn over(&self, dst: Vec4) -> Vec4 { PorterDuff::Over.apply(*self, dst) }
+    /// self composited over dst, premultiplied alpha
+    pub fn over_premul(&self, dst: Vec4) -> Vec4 { PorterDuff::Over.apply_premul(*self, dst) }
+}
+
+/// the porter duff operators, each keeping some part of the source and some part of the destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PorterDuff {
+    /// source on top of destination
+    Over,
+    /// source where destination is
+    In,
+    /// source where destination isn't
+    Out,
+    /// source where destination is, destination elsewhere
+    Atop,
+    /// source where destination isn't, destination where source isn't
+    Xor,
+}
+
+impl PorterDuff {
+    /// how much of source and destination to keep given their alphas
+    fn factors(self, src_a: f32, dst_a: f32) -> (f32, f32) {
+        match self {
+            PorterDuff::Over => (1.0, 1.0 - src_a),
+            PorterDuff::In => (dst_a, 0.0),
+            PorterDuff::Out => (1.0 - dst_a, 0.0),
+            PorterDuff::Atop => (dst_a, 1.0 - src_a),
+            PorterDuff::Xor => (1.0 - dst_a, 1.0 - src_a),
+        }
+    }
+    /// composite premultiplied colors, giving a premultiplied color
+    pub fn apply_premul(self, src: Vec4, dst: Vec4) -> Vec4 {
+        let (fs, fd) = self.factors(src.w, dst.w);
+        src * fs + dst * fd
+    }
+    /// composite straight alpha colors, giving a straight alpha color
+    pub fn apply(self, src: Vec4, dst: Vec4) -> Vec4 {
+        self.apply_premul(src.premultiply(), dst.premultiply()).unpremultiply()
+    }
+}
+
+/// the blend modes from the w3c compositing spec. the first twelve work channel by channel,
+/// hue, saturation, color and luminosity mix the channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+fn lum(c: Vec3) -> f32 { 0.3 * c.x + 0.59 * c.y + 0.11 * c.z }
+
+/// pull out of gamut colors back in while keeping their luminosity
+fn clip_color(c: Vec3) -> Vec3 {
+    let l = lum(c);
+    let (n, x) = (c.min_element(), c.max_element());
+    let mut c = c;
+    if n < 0.0 { c = Vec3::splat(l) + (c - Vec3::splat(l)) * (l / (l - n)); }
+    if x > 1.0 { c = Vec3::splat(l) + (c - Vec3::splat(l)) * ((1.0 - l) / (x - l)); }
+    c
+}
+
+fn set_lum(c: Vec3, l: f32) -> Vec3 { clip_color(c + Vec3::splat(l - lum(c))) }
+
+fn sat(c: Vec3) -> f32 { c.max_element() - c.min_element() }
+
+/// stretch c so its smallest channel is 0 and its largest is s
+fn set_sat(c: Vec3, s: f32) -> Vec3 {
+    let (n, x) = (c.min_element(), c.max_element());
+    if x > n { (c - Vec3::splat(n)) * (s / (x - n)) } else { Vec3::default() }
+}
+
+fn hard_light(b: f32, s: f32) -> f32 {
+    if s <= 0.5 { b * 2.0 * s } else { let s = 2.0 * s - 1.0; b + s - b * s }
+}
+
+fn soft_light(b: f32, s: f32) -> f32 {
+    if s <= 0.5 {
+        b - (1.0 - 2.0 * s) * b * (1.0 - b)
+    } else {
+        let d = if b <= 0.25 { ((16.0 * b - 12.0) * b + 4.0) * b } else { b.sqrt() };
+        b + (2.0 * s - 1.0) * (d - b)
+    }
+}
+
+fn color_dodge(b: f32, s: f32) -> f32 {
+    if b == 0.0 { 0.0 } else if s >= 1.0 { 1.0 } else { (b / (1.0 - s)).min(1.0) }
+}
+
+fn color_burn(b: f32, s: f32) -> f32 {
+    if b >= 1.0 { 1.0 } else if s <= 0.0 { 0.0 } else { 1.0 - ((1.0 - b) / s).min(1.0) }
+}
+
+impl BlendMode {
+    /// the mixed color for backdrop cb and source cs, ignoring alpha
+    pub fn blend_rgb(self, cb: Vec3, cs: Vec3) -> Vec3 {
+        let each = |f: fn(f32, f32) -> f32| cb.zip_map(cs, f);
+        match self {
+            BlendMode::Normal => cs,
+            BlendMode::Multiply => cb * cs,
+            BlendMode::Screen => cb + cs - cb * cs,
+            BlendMode::Overlay => each(|b, s| hard_light(s, b)),
+            BlendMode::Darken => cb.min(cs),
+            BlendMode::Lighten => cb.max(cs),
+            BlendMode::ColorDodge => each(color_dodge),
+            BlendMode::ColorBurn => each(color_burn),
+            BlendMode::HardLight => each(hard_light),
+            BlendMode::SoftLight => each(soft_light),
+            BlendMode::Difference => (cb - cs).abs(),
+            BlendMode::Exclusion => cb + cs - cb * cs * 2.0,
+            BlendMode::Hue => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+            BlendMode::Saturation => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+            BlendMode::Color => set_lum(cs, lum(cb)),
+            BlendMode::Luminosity => set_lum(cb, lum(cs)),
+        }
+    }
+    /// blend straight alpha src onto dst then composite it over, giving a straight alpha color
+    pub fn apply(self, src: Vec4, dst: Vec4) -> Vec4 {
+        let (cs, cb) = (src.truncate(), dst.truncate());
+        // where the backdrop is transparent the source shows through unblended
+        let mixed = cs * (1.0 - dst.w) + self.blend_rgb(cb, cs) * dst.w;
+        mixed.extend(src.w).over(dst)
+    }
+}
+
+#[test]
+fn test_premultiply() {
+    let c = vec4(0.5, 1.0, 0.25, 0.5);
+    assert_eq!(c.premultiply(), vec4(0.25, 0.5, 0.125, 0.5));
+    assert_eq!(c.premultiply().unpremultiply(), c);
+    assert_eq!(vec4(1.0, 1.0, 1.0, 0.0).unpremultiply(), Vec4::default());
+}
+
+#[test]
+fn test_porter_duff() {
+    let red = vec4(1.0, 0.0, 0.0, 0.5);
+    let blue = vec4(0.0, 0.0, 1.0, 1.0);
+    assert_eq!(red.over(blue), vec4(0.5, 0.0, 0.5, 1.0));
+    assert_eq!(red.premultiply().over_premul(blue), vec4(0.5, 0.0, 0.5, 1.0));
+    assert_eq!(blue.over(red), blue);
+    // half transparent over half transparent is 3/4 opaque, two thirds source color
+    let c = red.over(vec4(0.0, 0.0, 1.0, 0.5));
+    assert!((c - vec4(2.0 / 3.0, 0.0, 1.0 / 3.0, 0.75)).abs().max_element() < 1e-6);
+    let dst = vec4(0.0, 0.0, 1.0, 0.5);
+    assert_eq!(PorterDuff::In.apply(red, dst), vec4(1.0, 0.0, 0.0, 0.25));
+    assert_eq!(PorterDuff::Out.apply(red, dst), vec4(1.0, 0.0, 0.0, 0.25));
+    assert_eq!(PorterDuff::Atop.apply(red, dst), vec4(0.5, 0.0, 0.5, 0.5));
+    assert_eq!(PorterDuff::Xor.apply(red, dst), vec4(0.5, 0.0, 0.5, 0.5));
+    assert_eq!(PorterDuff::In.apply(red, Vec4::default()), Vec4::default());
+    assert_eq!(PorterDuff::Out.apply(red, blue), Vec4::default());
+    // premultiplied and straight agree
+    for op in [PorterDuff::Over, PorterDuff::In, PorterDuff::Out, PorterDuff::Atop, PorterDuff::Xor] {
+        let p = op.apply_premul(red.premultiply(), dst.premultiply()).unpremultiply();
+        assert!((p - op.apply(red, dst)).abs().max_element() < 1e-6);
+    }
+}
+
+#[test]
+fn test_separable_blend_modes() {
+    let b = vec3(0.25, 0.5, 0.75);
+    let s = vec3(0.75, 0.5, 0.25);
+    let close = |a: Vec3, e: Vec3| (a - e).abs().max_element() < 1e-6;
+    assert_eq!(BlendMode::Normal.blend_rgb(b, s), s);
+    assert!(close(BlendMode::Multiply.blend_rgb(b, s), vec3(0.1875, 0.25, 0.1875)));
+    assert!(close(BlendMode::Screen.blend_rgb(b, s), vec3(0.8125, 0.75, 0.8125)));
+    assert!(close(BlendMode::Overlay.blend_rgb(b, s), vec3(0.375, 0.5, 0.625)));
+    assert!(close(BlendMode::HardLight.blend_rgb(b, s), vec3(0.625, 0.5, 0.375)));
+    assert!(close(BlendMode::Darken.blend_rgb(b, s), vec3(0.25, 0.5, 0.25)));
+    assert!(close(BlendMode::Lighten.blend_rgb(b, s), vec3(0.75, 0.5, 0.75)));
+    assert!(close(BlendMode::ColorDodge.blend_rgb(b, s), vec3(1.0, 1.0, 1.0)));
+    assert!(close(BlendMode::ColorBurn.blend_rgb(b, s), vec3(0.0, 0.0, 0.0)));
+    assert!(close(BlendMode::ColorDodge.blend_rgb(vec3(0.0, 0.2, 0.2), vec3(0.5, 0.5, 1.0)), vec3(0.0, 0.4, 1.0)));
+    assert!(close(BlendMode::ColorBurn.blend_rgb(vec3(1.0, 0.8, 0.8), vec3(0.5, 0.5, 0.0)), vec3(1.0, 0.6, 0.0)));
+    // soft light: 0.25 under 0.75 uses the polynomial, 0.75 under 0.25 darkens
+    assert!(close(BlendMode::SoftLight.blend_rgb(b, s), vec3(0.375, 0.5, 0.65625)));
+    assert!(close(BlendMode::SoftLight.blend_rgb(vec3(0.64, 0.5, 0.5), vec3(1.0, 0.5, 0.5)), vec3(0.8, 0.5, 0.5)));
+    assert!(close(BlendMode::Difference.blend_rgb(b, s), vec3(0.5, 0.0, 0.5)));
+    assert!(close(BlendMode::Exclusion.blend_rgb(b, s), vec3(0.625, 0.5, 0.625)));
+}
+
+#[test]
+fn test_non_separable_blend_modes() {
+    let red = vec3(1.0, 0.0, 0.0);
+    let blue = vec3(0.0, 0.0, 1.0);
+    let grey = vec3(0.5, 0.5, 0.5);
+    let close = |a: Vec3, e: Vec3| (a - e).abs().max_element() < 1e-4;
+    // blue's hue at red's luminosity 0.3, clipped back into gamut
+    let h = BlendMode::Hue.blend_rgb(red, blue);
+    assert!(close(h, vec3(0.2135, 0.2135, 1.0)));
+    assert!((lum(h) - 0.3).abs() < 1e-5);
+    // grey has no saturation or hue to give
+    assert!(close(BlendMode::Saturation.blend_rgb(red, grey), vec3(0.3, 0.3, 0.3)));
+    assert!(close(BlendMode::Hue.blend_rgb(grey, grey), grey));
+    assert!(close(BlendMode::Color.blend_rgb(grey, vec3(0.6, 0.4, 0.4)), vec3(0.64, 0.44, 0.44)));
+    assert!(close(BlendMode::Luminosity.blend_rgb(vec3(0.6, 0.4, 0.4), grey), vec3(0.64, 0.44, 0.44)));
+    let c = BlendMode::Color.blend_rgb(vec3(0.2, 0.7, 0.1), vec3(0.9, 0.3, 0.5));
+    assert!((lum(c) - lum(vec3(0.2, 0.7, 0.1))).abs() < 1e-5);
+}
+
+#[test]
+fn test_blend_apply() {
+    let dst = vec4(0.25, 0.5, 0.75, 1.0);
+    let src = vec4(0.75, 0.5, 0.25, 1.0);
+    assert!((BlendMode::Multiply.apply(src, dst) - vec4(0.1875, 0.25, 0.1875, 1.0)).abs().max_element() < 1e-6);
+    // half transparent source is half way between backdrop and blend result
+    let half = BlendMode::Multiply.apply(src.truncate().extend(0.5), dst);
+    assert!((half - vec4(0.21875, 0.375, 0.46875, 1.0)).abs().max_element() < 1e-6);
+    // onto nothing the source is unchanged
+    assert_eq!(BlendMode::Difference.apply(src, Vec4::default()), src);
+    assert_eq!(BlendMode::Normal.apply(src, dst), src);
+}
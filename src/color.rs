@@ -0,0 +1,281 @@
+// the matrices are quoted at the precision they were published with
+#![allow(clippy::excessive_precision)]
+
+use crate::*;
+
+// colors live in Vec4 as (r, g, b, a) or the equivalent for each space, alpha
+// passes through every conversion untouched. hues are in degrees in [0, 360),
+// s, v, l in [0, 1]. rgb here means linear or srgb encoded depending on what
+// you feed in, except the xyz/lab/oklab conversions which want linear rgb.
+// lab is the cie one with a d65 white point, L in [0, 100]
+
+const LINEAR_TO_XYZ: [Vec3; 3] = [
+    vec3(0.4124564, 0.3575761, 0.1804375),
+    vec3(0.2126729, 0.7151522, 0.0721750),
+    vec3(0.0193339, 0.1191920, 0.9503041),
+];
+const XYZ_TO_LINEAR: [Vec3; 3] = [
+    vec3(3.2404542, -1.5371385, -0.4985314),
+    vec3(-0.9692660, 1.8760108, 0.0415560),
+    vec3(0.0556434, -0.2040259, 1.0572252),
+];
+/// xyz of the d65 white point, the one srgb and lab here use
+pub const WHITE_D65: Vec3 = vec3(0.95047, 1.0, 1.08883);
+/// xyz of the d50 white point, the one print and icc profiles use
+pub const WHITE_D50: Vec3 = vec3(0.96422, 1.0, 0.82521);
+
+const LINEAR_TO_LMS: [Vec3; 3] = [
+    vec3(0.4122214708, 0.5363325363, 0.0514459929),
+    vec3(0.2119034982, 0.6806995451, 0.1073969566),
+    vec3(0.0883024619, 0.2817188376, 0.6299787005),
+];
+const LMS_TO_OKLAB: [Vec3; 3] = [
+    vec3(0.2104542553, 0.7936177850, -0.0040720468),
+    vec3(1.9779984951, -2.4285922050, 0.4505937099),
+    vec3(0.0259040371, 0.7827717662, -0.8086757660),
+];
+const OKLAB_TO_LMS: [Vec3; 3] = [
+    vec3(1.0, 0.3963377774, 0.2158037573),
+    vec3(1.0, -0.1055613458, -0.0638541728),
+    vec3(1.0, -0.0894841775, -1.2914855480),
+];
+const LMS_TO_LINEAR: [Vec3; 3] = [
+    vec3(4.0767416621, -3.3077115913, 0.2309699292),
+    vec3(-1.2684380046, 2.6097574011, -0.3413193965),
+    vec3(-0.0041960863, -0.7034186147, 1.7076147010),
+];
+
+pub(crate) fn mul_rows(m: &[Vec3; 3], v: Vec3) -> Vec3 { vec3(m[0].dot(v), m[1].dot(v), m[2].dot(v)) }
+
+/// hue in [0, 360) from whichever channel is largest, 0 for greys
+fn hue(c: Vec3, max: f32, d: f32) -> f32 {
+    if d == 0.0 { return 0.0; }
+    let h = if max == c.x {
+        (c.y - c.z) / d
+    } else if max == c.y {
+        (c.z - c.x) / d + 2.0
+    } else {
+        (c.x - c.y) / d + 4.0
+    };
+    (h * 60.0).rem_euclid(360.0) % 360.0
+}
+
+/// srgb transfer function, encoded [0, 1] to linear light. mirrored for negative values
+pub fn srgb_to_linear(c: f32) -> f32 {
+    let a = c.abs();
+    let l = if a <= 0.04045 { a / 12.92 } else { ((a + 0.055) / 1.055).powf(2.4) };
+    l.copysign(c)
+}
+
+/// inverse of srgb_to_linear
+pub fn linear_to_srgb(c: f32) -> f32 {
+    let a = c.abs();
+    let e = if a <= 0.0031308 { a * 12.92 } else { 1.055 * a.powf(1.0 / 2.4) - 0.055 };
+    e.copysign(c)
+}
+
+impl Vec4 {
+    /// (h, s, v, a) to (r, g, b, a). any hue works, it's wrapped into [0, 360)
+    pub fn hsv_to_rgb(&self) -> Vec4 {
+        let (h, s, v) = (self.x.rem_euclid(360.0) / 60.0, self.y, self.z);
+        let f = |n: f32| {
+            let k = (n + h) % 6.0;
+            v - v * s * k.min(4.0 - k).clamp(0.0, 1.0)
+        };
+        vec4(f(5.0), f(3.0), f(1.0), self.w)
+    }
+    /// (r, g, b, a) to (h, s, v, a)
+    pub fn rgb_to_hsv(&self) -> Vec4 {
+        let c = self.truncate();
+        let (max, min) = (c.max_element(), c.min_element());
+        let d = max - min;
+        let s = if max == 0.0 { 0.0 } else { d / max };
+        vec4(hue(c, max, d), s, max, self.w)
+    }
+    /// (h, s, l, a) to (r, g, b, a). any hue works, it's wrapped into [0, 360)
+    pub fn hsl_to_rgb(&self) -> Vec4 {
+        let (h, s, l) = (self.x.rem_euclid(360.0) / 30.0, self.y, self.z);
+        let a = s * l.min(1.0 - l);
+        let f = |n: f32| {
+            let k = (n + h) % 12.0;
+            l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        vec4(f(0.0), f(8.0), f(4.0), self.w)
+    }
+    /// (r, g, b, a) to (h, s, l, a)
+    pub fn rgb_to_hsl(&self) -> Vec4 {
+        let c = self.truncate();
+        let (max, min) = (c.max_element(), c.min_element());
+        let d = max - min;
+        let l = (max + min) / 2.0;
+        let s = if d == 0.0 { 0.0 } else { d / (1.0 - (2.0 * l - 1.0).abs()) };
+        vec4(hue(c, max, d), s, l, self.w)
+    }
+    /// srgb encoded rgb to linear rgb
+    pub fn srgb_to_linear(&self) -> Vec4 { vec4(srgb_to_linear(self.x), srgb_to_linear(self.y), srgb_to_linear(self.z), self.w) }
+    /// linear rgb to srgb encoded rgb
+    pub fn linear_to_srgb(&self) -> Vec4 { vec4(linear_to_srgb(self.x), linear_to_srgb(self.y), linear_to_srgb(self.z), self.w) }
+    /// linear srgb to cie xyz (d65)
+    pub fn linear_to_xyz(&self) -> Vec4 { mul_rows(&LINEAR_TO_XYZ, self.truncate()).extend(self.w) }
+    /// cie xyz (d65) to linear srgb
+    pub fn xyz_to_linear(&self) -> Vec4 { mul_rows(&XYZ_TO_LINEAR, self.truncate()).extend(self.w) }
+    /// cie xyz to cie lab
+    pub fn xyz_to_lab(&self) -> Vec4 {
+        let d = 6.0f32 / 29.0;
+        let f = |t: f32| if t > d * d * d { t.cbrt() } else { t / (3.0 * d * d) + 4.0 / 29.0 };
+        let c = self.truncate() / WHITE_D65;
+        let (fx, fy, fz) = (f(c.x), f(c.y), f(c.z));
+        vec4(116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz), self.w)
+    }
+    /// cie lab to cie xyz
+    pub fn lab_to_xyz(&self) -> Vec4 {
+        let d = 6.0f32 / 29.0;
+        let f = |t: f32| if t > d { t * t * t } else { 3.0 * d * d * (t - 4.0 / 29.0) };
+        let fy = (self.x + 16.0) / 116.0;
+        let c = vec3(f(fy + self.y / 500.0), f(fy), f(fy - self.z / 200.0));
+        (c * WHITE_D65).extend(self.w)
+    }
+    /// (L, a, b) to (L, chroma, hue)
+    pub fn lab_to_lch(&self) -> Vec4 {
+        let h = self.z.atan2(self.y).to_degrees().rem_euclid(360.0) % 360.0;
+        vec4(self.x, self.y.hypot(self.z), h, self.w)
+    }
+    /// (L, chroma, hue) to (L, a, b)
+    pub fn lch_to_lab(&self) -> Vec4 {
+        let (s, c) = self.z.to_radians().sin_cos();
+        vec4(self.x, self.y * c, self.y * s, self.w)
+    }
+    /// linear srgb to cie lab
+    pub fn linear_to_lab(&self) -> Vec4 { self.linear_to_xyz().xyz_to_lab() }
+    /// cie lab to linear srgb
+    pub fn lab_to_linear(&self) -> Vec4 { self.lab_to_xyz().xyz_to_linear() }
+    /// linear srgb to oklab, L in [0, 1]
+    pub fn linear_to_oklab(&self) -> Vec4 {
+        let lms = mul_rows(&LINEAR_TO_LMS, self.truncate());
+        mul_rows(&LMS_TO_OKLAB, vec3(lms.x.cbrt(), lms.y.cbrt(), lms.z.cbrt())).extend(self.w)
+    }
+    /// oklab to linear srgb
+    pub fn oklab_to_linear(&self) -> Vec4 {
+        let lms = mul_rows(&OKLAB_TO_LMS, self.truncate());
+        mul_rows(&LMS_TO_LINEAR, lms * lms * lms).extend(self.w)
+    }
+    /// (L, a, b) to (L, chroma, hue), same as lab_to_lch
+    pub fn oklab_to_oklch(&self) -> Vec4 { self.lab_to_lch() }
+    /// (L, chroma, hue) to (L, a, b), same as lch_to_lab
+    pub fn oklch_to_oklab(&self) -> Vec4 { self.lch_to_lab() }
+
+    /// channels clamped to [0, 1] and rounded to the nearest of 0..=255, in r, g, b, a order
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        let q = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [q(self.x), q(self.y), q(self.z), q(self.w)]
+    }
+    pub fn from_rgba8(c: [u8; 4]) -> Vec4 { vec4(c[0] as f32, c[1] as f32, c[2] as f32, c[3] as f32) / 255.0 }
+    /// packed as 0xRRGGBBAA
+    pub fn to_rgba_u32(&self) -> u32 { u32::from_be_bytes(self.to_rgba8()) }
+    pub fn from_rgba_u32(c: u32) -> Vec4 { Vec4::from_rgba8(c.to_be_bytes()) }
+    /// packed as 0xAARRGGBB
+    pub fn to_argb_u32(&self) -> u32 { self.to_rgba_u32().rotate_right(8) }
+    pub fn from_argb_u32(c: u32) -> Vec4 { Vec4::from_rgba_u32(c.rotate_left(8)) }
+    /// packed as 0xBBGGRRAA
+    pub fn to_bgra_u32(&self) -> u32 {
+        let [r, g, b, a] = self.to_rgba8();
+        u32::from_be_bytes([b, g, r, a])
+    }
+    pub fn from_bgra_u32(c: u32) -> Vec4 {
+        let [b, g, r, a] = c.to_be_bytes();
+        Vec4::from_rgba8([r, g, b, a])
+    }
+    /// "#rrggbb", or "#rrggbbaa" when not opaque
+    pub fn to_hex(&self) -> String {
+        let [r, g, b, a] = self.to_rgba8();
+        if a == 255 { format!("#{:02x}{:02x}{:02x}", r, g, b) } else { format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a) }
+    }
+}
+
+#[cfg(test)]
+fn test_colors() -> Vec<Vec4> {
+    let mut v = Vec::new();
+    for r in 0..5 {
+        for g in 0..5 {
+            for b in 0..5 {
+                v.push(vec4(r as f32 / 4.0, g as f32 / 4.0, b as f32 / 4.0, 0.5));
+            }
+        }
+    }
+    v.push(vec4(0.3, 0.7, 0.1, 1.0));
+    v.push(vec4(0.95, 0.05, 0.6, 0.0));
+    v
+}
+
+#[cfg(test)]
+fn assert_round_trip(to: fn(&Vec4) -> Vec4, from: fn(&Vec4) -> Vec4, tol: f32) {
+    for c in test_colors() {
+        let back = from(&to(&c));
+        assert!((back - c).abs().max_element() < tol, "{} -> {} -> {}", c, to(&c), back);
+    }
+}
+
+#[test]
+fn test_color_round_trips() {
+    assert_round_trip(Vec4::rgb_to_hsv, Vec4::hsv_to_rgb, 1e-5);
+    assert_round_trip(Vec4::rgb_to_hsl, Vec4::hsl_to_rgb, 1e-5);
+    assert_round_trip(Vec4::srgb_to_linear, Vec4::linear_to_srgb, 1e-5);
+    assert_round_trip(Vec4::linear_to_srgb, Vec4::srgb_to_linear, 1e-5);
+    assert_round_trip(Vec4::linear_to_xyz, Vec4::xyz_to_linear, 1e-5);
+    assert_round_trip(Vec4::linear_to_lab, Vec4::lab_to_linear, 1e-4);
+    assert_round_trip(|c| c.linear_to_lab().lab_to_lch(), |c| c.lch_to_lab().lab_to_linear(), 1e-4);
+    assert_round_trip(Vec4::linear_to_oklab, Vec4::oklab_to_linear, 1e-4);
+    assert_round_trip(|c| c.linear_to_oklab().oklab_to_oklch(), |c| c.oklch_to_oklab().oklab_to_linear(), 1e-4);
+}
+
+#[test]
+fn test_color_known_values() {
+    let close = |a: Vec4, b: Vec4, tol: f32| (a - b).abs().max_element() < tol;
+    let red = vec4(1.0, 0.0, 0.0, 1.0);
+    assert_eq!(red.rgb_to_hsv(), vec4(0.0, 1.0, 1.0, 1.0));
+    assert_eq!(vec4(0.0, 0.0, 1.0, 1.0).rgb_to_hsl(), vec4(240.0, 1.0, 0.5, 1.0));
+    assert_eq!(vec4(0.5, 0.5, 0.5, 1.0).rgb_to_hsv(), vec4(0.0, 0.0, 0.5, 1.0));
+    assert!(close(vec4(120.0, 1.0, 1.0, 1.0).hsv_to_rgb(), vec4(0.0, 1.0, 0.0, 1.0), 1e-6));
+    assert!(close(vec4(60.0, 1.0, 0.5, 1.0).hsl_to_rgb(), vec4(1.0, 1.0, 0.0, 1.0), 1e-6));
+    // negative and large hues wrap instead of panicking
+    assert!(close(vec4(-120.0, 1.0, 1.0, 1.0).hsv_to_rgb(), vec4(0.0, 0.0, 1.0, 1.0), 1e-6));
+    assert!(close(vec4(-1e-9, 1.0, 1.0, 1.0).hsv_to_rgb(), red, 1e-6));
+    assert!(close(vec4(720.0, 1.0, 0.5, 1.0).hsl_to_rgb(), red, 1e-6));
+    assert!(close(vec4(-400.0, 0.3, 0.6, 1.0).hsl_to_rgb(), vec4(320.0, 0.3, 0.6, 1.0).hsl_to_rgb(), 1e-5));
+    assert!(vec4(f32::NAN, 1.0, 1.0, 1.0).hsv_to_rgb().w == 1.0);
+    assert!((srgb_to_linear(0.5) - 0.21404).abs() < 1e-5);
+    assert!((linear_to_srgb(0.0) - 0.0).abs() < 1e-6);
+    // white is L = 100 in lab and L = 1 in oklab, both with no chroma
+    let white = vec4(1.0, 1.0, 1.0, 1.0);
+    assert!(close(white.linear_to_xyz(), vec4(0.95047, 1.0, 1.08883, 1.0), 1e-4));
+    assert!(close(white.linear_to_lab(), vec4(100.0, 0.0, 0.0, 1.0), 1e-2));
+    assert!(close(white.linear_to_oklab(), vec4(1.0, 0.0, 0.0, 1.0), 1e-3));
+    // reference values for srgb red
+    assert!(close(red.linear_to_lab(), vec4(53.24, 80.09, 67.20, 1.0), 2e-2));
+    assert!(close(red.linear_to_oklab(), vec4(0.62796, 0.22486, 0.12585, 1.0), 1e-4));
+    let lch = red.linear_to_oklab().oklab_to_oklch();
+    assert!((lch.y - 0.25768).abs() < 1e-4 && (lch.z - 29.234).abs() < 1e-2);
+}
+
+#[test]
+fn test_color_packing() {
+    let c = vec4(1.0, 0.5, 0.0, 0.2);
+    // 0.5 * 255 = 127.5 rounds up, 0.2 * 255 = 51
+    assert_eq!(c.to_rgba8(), [255, 128, 0, 51]);
+    assert_eq!(vec4(-1.0, 2.0, 0.499 / 255.0, 0.501 / 255.0).to_rgba8(), [0, 255, 0, 1]);
+    assert_eq!(c.to_rgba_u32(), 0xff800033);
+    assert_eq!(c.to_argb_u32(), 0x33ff8000);
+    assert_eq!(c.to_bgra_u32(), 0x0080ff33);
+    for i in 0..=255u8 {
+        let c = Vec4::from_rgba8([i, 255 - i, i / 2, i / 3]);
+        assert_eq!(c.to_rgba8(), [i, 255 - i, i / 2, i / 3]);
+        let p = 0x01020304u32.wrapping_mul(i as u32 + 1);
+        assert_eq!(Vec4::from_rgba_u32(p).to_rgba_u32(), p);
+        assert_eq!(Vec4::from_argb_u32(p).to_argb_u32(), p);
+        assert_eq!(Vec4::from_bgra_u32(p).to_bgra_u32(), p);
+    }
+    assert_eq!(Vec4::from_argb_u32(0x80ff0000), vec4(1.0, 0.0, 0.0, 128.0 / 255.0));
+    assert_eq!(Vec4::from_bgra_u32(0xff0000ff), vec4(0.0, 0.0, 1.0, 1.0));
+    assert_eq!(vec4(1.0, 0.5, 0.0, 1.0).to_hex(), "#ff8000");
+    assert_eq!(c.to_hex(), "#ff800033");
+}
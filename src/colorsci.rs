@@ -0,0 +1,256 @@
+// the matrices are quoted at the precision they were published with
+#![allow(clippy::excessive_precision)]
+
+use crate::*;
+use crate::color::mul_rows;
+
+// luminance and contrast, color differences, color temperature, chromatic
+// adaptation and tone mapping
+
+const BRADFORD: [Vec3; 3] = [
+    vec3(0.8951, 0.2664, -0.1614),
+    vec3(-0.7502, 1.7135, 0.0367),
+    vec3(0.0389, -0.0685, 1.0296),
+];
+const BRADFORD_INV: [Vec3; 3] = [
+    vec3(0.9869929, -0.1470543, 0.1599627),
+    vec3(0.4323053, 0.5183603, 0.0492912),
+    vec3(-0.0085287, 0.0400428, 0.9684867),
+];
+
+impl Vec4 {
+    /// wcag relative luminance of an srgb encoded color, 0 for black and 1 for white
+    pub fn relative_luminance(&self) -> f32 {
+        let c = self.srgb_to_linear();
+        0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z
+    }
+    /// wcag contrast ratio between two srgb encoded colors, from 1 to 21 whichever way round.
+    /// body text wants at least 4.5
+    pub fn contrast_ratio(&self, other: Vec4) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+    /// cie76 color difference between two lab colors, plain euclidean distance
+    pub fn delta_e76(&self, other: Vec4) -> f32 { self.truncate().dist(other.truncate()) }
+    /// ciede2000 color difference between two lab colors. around 1 is just noticeable
+    pub fn delta_e2000(&self, other: Vec4) -> f32 {
+        let (l1, a1, b1) = (self.x as f64, self.y as f64, self.z as f64);
+        let (l2, a2, b2) = (other.x as f64, other.y as f64, other.z as f64);
+        let p7 = |c: f64| { let c7 = c.powi(7); (c7 / (c7 + 25f64.powi(7))).sqrt() };
+        let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+        let g = 0.5 * (1.0 - p7(c_bar));
+        let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+        let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+        let hue = |b: f64, a: f64| if a == 0.0 && b == 0.0 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+        let (h1, h2) = (hue(b1, a1), hue(b2, a2));
+        let chroma_zero = c1 * c2 == 0.0;
+        let dh = if chroma_zero {
+            0.0
+        } else if (h2 - h1).abs() <= 180.0 {
+            h2 - h1
+        } else if h2 > h1 {
+            h2 - h1 - 360.0
+        } else {
+            h2 - h1 + 360.0
+        };
+        let d_l = l2 - l1;
+        let d_c = c2 - c1;
+        let d_h = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+        let l_bar = (l1 + l2) / 2.0;
+        let c_bar = (c1 + c2) / 2.0;
+        let h_bar = if chroma_zero {
+            h1 + h2
+        } else if (h1 - h2).abs() <= 180.0 {
+            (h1 + h2) / 2.0
+        } else if h1 + h2 < 360.0 {
+            (h1 + h2 + 360.0) / 2.0
+        } else {
+            (h1 + h2 - 360.0) / 2.0
+        };
+        let cos = |deg: f64| deg.to_radians().cos();
+        let t = 1.0 - 0.17 * cos(h_bar - 30.0) + 0.24 * cos(2.0 * h_bar) + 0.32 * cos(3.0 * h_bar + 6.0) - 0.20 * cos(4.0 * h_bar - 63.0);
+        let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+        let r_c = 2.0 * p7(c_bar);
+        let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+        let s_c = 1.0 + 0.045 * c_bar;
+        let s_h = 1.0 + 0.015 * c_bar * t;
+        let r_t = -(2.0 * d_theta).to_radians().sin() * r_c;
+        let (l, c, h) = (d_l / s_l, d_c / s_c, d_h / s_h);
+        (l * l + c * c + h * h + r_t * c * h).sqrt() as f32
+    }
+    /// xyz color seen under src_white to how it looks under dst_white, bradford method
+    pub fn adapt_xyz(&self, src_white: Vec3, dst_white: Vec3) -> Vec4 {
+        let scale = mul_rows(&BRADFORD, dst_white) / mul_rows(&BRADFORD, src_white);
+        let cone = mul_rows(&BRADFORD, self.truncate()) * scale;
+        mul_rows(&BRADFORD_INV, cone).extend(self.w)
+    }
+    /// linear rgb shot under light of src_kelvin corrected to look as if lit by dst_kelvin
+    pub fn white_balance(&self, src_kelvin: f32, dst_kelvin: f32) -> Vec4 {
+        let white = |k: f32| { let p = planckian_xy(k); vec3(p.x / p.y, 1.0, (1.0 - p.x - p.y) / p.y) };
+        self.linear_to_xyz().adapt_xyz(white(src_kelvin), white(dst_kelvin)).xyz_to_linear()
+    }
+}
+
+/// cie xy chromaticity of a black body at this temperature, kim et al's fit to the
+/// planckian locus. good from 1667K to 25000K, clamped to that
+pub fn planckian_xy(kelvin: f32) -> Vec2 {
+    let t = kelvin.clamp(1667.0, 25000.0) as f64;
+    let (t2, t3) = (t * t, t * t * t);
+    let x = if t <= 4000.0 {
+        -0.2661239e9 / t3 - 0.2343589e6 / t2 + 0.8776956e3 / t + 0.179910
+    } else {
+        -3.0258469e9 / t3 + 2.1070379e6 / t2 + 0.2226347e3 / t + 0.240390
+    };
+    let (x2, x3) = (x * x, x * x * x);
+    let y = if t <= 2222.0 {
+        -1.1063814 * x3 - 1.34811020 * x2 + 2.18555832 * x - 0.20219683
+    } else if t <= 4000.0 {
+        -0.9549476 * x3 - 1.37418593 * x2 + 2.09137015 * x - 0.16748867
+    } else {
+        3.0817580 * x3 - 5.87338670 * x2 + 3.75112997 * x - 0.37001483
+    };
+    vec2(x as f32, y as f32)
+}
+
+/// opaque linear rgb color of a black body at this temperature, scaled so the
+/// brightest channel is 1. out of gamut channels clamp to 0
+pub fn kelvin_to_rgb(kelvin: f32) -> Vec4 {
+    let p = planckian_xy(kelvin);
+    let xyz = vec4(p.x / p.y, 1.0, (1.0 - p.x - p.y) / p.y, 1.0);
+    let c = xyz.xyz_to_linear().truncate().max(Vec3::splat(0.0));
+    (c / c.max_element()).extend(1.0)
+}
+
+/// operators squashing unbounded linear hdr rgb into [0, 1] linear rgb
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tonemap {
+    /// c / (1 + c), never quite reaches white
+    Reinhard,
+    /// reinhard that reaches 1 at the given white level
+    ReinhardExtended(f32),
+    /// narkowicz's fit of the aces filmic curve, contrasty and saturated
+    Aces,
+    /// agx, desaturates bright colors towards white the way film does
+    Agx,
+}
+
+const AGX_INSET: [Vec3; 3] = [
+    vec3(0.842479062253094, 0.0784335999999992, 0.0792237451477643),
+    vec3(0.0423282422610123, 0.878468636469772, 0.0791661274605434),
+    vec3(0.0423756549057051, 0.0784336, 0.879142973793104),
+];
+const AGX_OUTSET: [Vec3; 3] = [
+    vec3(1.19687900512017, -0.0980208811401368, -0.0990297440797205),
+    vec3(-0.0528968517574562, 1.15190312990417, -0.0989611768448433),
+    vec3(-0.0529716355144438, -0.0980434501171241, 1.15107367264116),
+];
+
+fn agx(c: Vec3) -> Vec3 {
+    let (min_ev, max_ev) = (-12.47393f32, 4.026069f32);
+    let c = mul_rows(&AGX_INSET, c);
+    let c = c.map(|x| (x.max(1e-10).log2().clamp(min_ev, max_ev) - min_ev) / (max_ev - min_ev));
+    // polynomial fit of the agx sigmoid, gives display encoded values
+    let c = c.map(|x| {
+        let (x2, x4) = (x * x, x * x * x * x);
+        15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x - 0.00232
+    });
+    mul_rows(&AGX_OUTSET, c).map(|x| x.max(0.0).powf(2.2)).clamp(Vec3::splat(0.0), Vec3::splat(1.0))
+}
+
+impl Tonemap {
+    pub fn apply(self, c: Vec3) -> Vec3 {
+        match self {
+            Tonemap::Reinhard => c / (c + Vec3::splat(1.0)),
+            Tonemap::ReinhardExtended(w) => (c * (c / (w * w) + Vec3::splat(1.0)) / (c + Vec3::splat(1.0))).min(Vec3::splat(1.0)),
+            Tonemap::Aces => {
+                let c = c.map(|x| (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14));
+                c.clamp(Vec3::splat(0.0), Vec3::splat(1.0))
+            }
+            Tonemap::Agx => agx(c),
+        }
+    }
+    /// apply to the rgb of an (r, g, b, a) color, alpha untouched
+    pub fn apply_rgba(self, c: Vec4) -> Vec4 { self.apply(c.truncate()).extend(c.w) }
+}
+
+#[test]
+fn test_luminance_contrast() {
+    let black = vec4(0.0, 0.0, 0.0, 1.0);
+    let white = vec4(1.0, 1.0, 1.0, 1.0);
+    assert_eq!(black.relative_luminance(), 0.0);
+    assert!((white.relative_luminance() - 1.0).abs() < 1e-6);
+    assert!((white.contrast_ratio(black) - 21.0).abs() < 1e-4);
+    assert!((black.contrast_ratio(white) - 21.0).abs() < 1e-4);
+    assert_eq!(white.contrast_ratio(white), 1.0);
+    // #767676 on white is the classic just-passes-aa grey, #777777 just fails
+    let g76 = css_parse_color("#767676").unwrap();
+    let g77 = css_parse_color("#777777").unwrap();
+    assert!(g76.contrast_ratio(white) >= 4.5);
+    assert!(g77.contrast_ratio(white) < 4.5);
+}
+
+#[test]
+fn test_delta_e() {
+    assert_eq!(vec4(50.0, 0.0, 0.0, 1.0).delta_e76(vec4(53.0, 4.0, 0.0, 1.0)), 5.0);
+    // from sharma, wu and dalal's ciede2000 test data
+    let cases = [
+        ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+        ((50.0, 3.1571, -77.2803), (50.0, 0.0, -82.7485), 2.8615),
+        ((50.0, 2.8361, -74.0200), (50.0, 0.0, -82.7485), 3.4412),
+        ((50.0, -1.3802, -84.2814), (50.0, 0.0, -82.7485), 1.0000),
+        ((50.0, 0.0, 0.0), (50.0, -1.0, 2.0), 2.3669),
+        ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+    ];
+    for (a, b, e) in cases {
+        let (a, b) = (vec4(a.0, a.1, a.2, 1.0), vec4(b.0, b.1, b.2, 1.0));
+        assert!((a.delta_e2000(b) - e).abs() < 1e-3, "{} {} {}", a, b, a.delta_e2000(b));
+        assert!((b.delta_e2000(a) - e).abs() < 1e-3);
+    }
+    assert_eq!(vec4(50.0, 10.0, 10.0, 1.0).delta_e2000(vec4(50.0, 10.0, 10.0, 1.0)), 0.0);
+}
+
+#[test]
+fn test_kelvin_and_adaptation() {
+    // illuminant a is a 2856K black body
+    let a = planckian_xy(2856.0);
+    assert!((a - vec2(0.4476, 0.4074)).abs().max_element() < 2e-3);
+    let warm = kelvin_to_rgb(2000.0);
+    let cool = kelvin_to_rgb(12000.0);
+    assert_eq!(warm.x, 1.0);
+    assert!(warm.z < warm.y && warm.y < warm.x);
+    assert_eq!(cool.z, 1.0);
+    assert!(cool.x < cool.z);
+    let day = kelvin_to_rgb(6500.0);
+    assert!(day.min_element() > 0.85);
+    // the published bradford d65 to d50 matrix, first column
+    let x = vec4(1.0, 0.0, 0.0, 1.0).adapt_xyz(WHITE_D65, WHITE_D50);
+    assert!((x - vec4(1.0478112, 0.0295424, -0.0092345, 1.0)).abs().max_element() < 1e-4);
+    let w = WHITE_D65.extend(0.5).adapt_xyz(WHITE_D65, WHITE_D50);
+    assert!((w - WHITE_D50.extend(0.5)).abs().max_element() < 1e-5);
+    // a white surface under warm light comes out neutral when balanced back to daylight
+    let surface = kelvin_to_rgb(3000.0);
+    let balanced = surface.white_balance(3000.0, 6500.0);
+    let target = kelvin_to_rgb(6500.0) * (balanced.y / kelvin_to_rgb(6500.0).y);
+    assert!((balanced - target.truncate().extend(1.0)).abs().max_element() < 1e-2);
+}
+
+#[test]
+fn test_tonemap() {
+    let ops = [Tonemap::Reinhard, Tonemap::ReinhardExtended(4.0), Tonemap::Aces, Tonemap::Agx];
+    for op in ops {
+        let mut last = -1.0;
+        for i in 0..200 {
+            let x = (i as f32 * 0.1 - 10.0).exp2();
+            let c = op.apply(Vec3::splat(x));
+            assert!(c.is_finite() && c.min_element() >= 0.0 && c.max_element() <= 1.0, "{:?} {}", op, x);
+            assert!(c.y >= last, "{:?} not monotonic at {}", op, x);
+            last = c.y;
+        }
+        assert!(op.apply(Vec3::splat(0.0)).max_element() < 0.01, "{:?}", op);
+        assert!(op.apply(Vec3::splat(1000.0)).min_element() > 0.95, "{:?}", op);
+    }
+    assert_eq!(Tonemap::Reinhard.apply(Vec3::splat(1.0)), Vec3::splat(0.5));
+    assert_eq!(Tonemap::ReinhardExtended(4.0).apply(Vec3::splat(4.0)), Vec3::splat(1.0));
+    assert!((Tonemap::Aces.apply(Vec3::splat(0.18)).x - 0.2672).abs() < 1e-3);
+    assert_eq!(Tonemap::Aces.apply_rgba(vec4(0.0, 0.0, 0.0, 0.3)).w, 0.3);
+}
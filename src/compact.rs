@@ -0,0 +1,139 @@
+//! serde helpers writing vectors and rects as plain arrays, `[1.0, 2.0]` instead of
+//! `{"x": 1.0, "y": 2.0}`. opt in per field:
+//!
+//! ```
+//! use minvect::*;
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Sprite {
+//!     #[serde(with = "minvect::compact")]
+//!     pos: Vec2,
+//!     #[serde(with = "minvect::compact::vec")]
+//!     outline: Vec<Vec2>,
+//! }
+//! ```
+//!
+//! the normal derived impls read arrays as well, so files written either way load
+//! either way. matrices are already plain arrays and need nothing
+
+use crate::*;
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+use serde::de::DeserializeOwned;
+
+/// a type with an array form
+pub trait Compact: Sized {
+    type Repr: Serialize + DeserializeOwned;
+    fn to_repr(&self) -> Self::Repr;
+    fn from_repr(r: Self::Repr) -> Self;
+}
+
+impl Compact for Vec2 {
+    type Repr = [f32; 2];
+    fn to_repr(&self) -> [f32; 2] { [self.x, self.y] }
+    fn from_repr(r: [f32; 2]) -> Vec2 { vec2(r[0], r[1]) }
+}
+impl Compact for Vec3 {
+    type Repr = [f32; 3];
+    fn to_repr(&self) -> [f32; 3] { [self.x, self.y, self.z] }
+    fn from_repr(r: [f32; 3]) -> Vec3 { vec3(r[0], r[1], r[2]) }
+}
+impl Compact for Vec4 {
+    type Repr = [f32; 4];
+    fn to_repr(&self) -> [f32; 4] { [self.x, self.y, self.z, self.w] }
+    fn from_repr(r: [f32; 4]) -> Vec4 { vec4(r[0], r[1], r[2], r[3]) }
+}
+/// [x, y, w, h]
+impl Compact for Rect {
+    type Repr = [f32; 4];
+    fn to_repr(&self) -> [f32; 4] { [self.xy.x, self.xy.y, self.wh.x, self.wh.y] }
+    fn from_repr(r: [f32; 4]) -> Rect { rect(r[0], r[1], r[2], r[3]) }
+}
+
+pub fn serialize<T: Compact, S: Serializer>(v: &T, s: S) -> Result<S::Ok, S::Error> { v.to_repr().serialize(s) }
+pub fn deserialize<'de, T: Compact, D: Deserializer<'de>>(d: D) -> Result<T, D::Error> { T::Repr::deserialize(d).map(T::from_repr) }
+
+/// the same for `Vec<T>` fields
+pub mod vec {
+    use super::*;
+    pub fn serialize<T: Compact, S: Serializer>(v: &[T], s: S) -> Result<S::Ok, S::Error> { s.collect_seq(v.iter().map(|x| x.to_repr())) }
+    pub fn deserialize<'de, T: Compact, D: Deserializer<'de>>(d: D) -> Result<Vec<T>, D::Error> {
+        Vec::<T::Repr>::deserialize(d).map(|v| v.into_iter().map(T::from_repr).collect())
+    }
+}
+
+/// the same for `Option<T>` fields
+pub mod option {
+    use super::*;
+    pub fn serialize<T: Compact, S: Serializer>(v: &Option<T>, s: S) -> Result<S::Ok, S::Error> { v.as_ref().map(|x| x.to_repr()).serialize(s) }
+    pub fn deserialize<'de, T: Compact, D: Deserializer<'de>>(d: D) -> Result<Option<T>, D::Error> {
+        Option::<T::Repr>::deserialize(d).map(|v| v.map(T::from_repr))
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Scene {
+    #[serde(with = "crate::compact")]
+    pos: Vec2,
+    #[serde(with = "crate::compact")]
+    color: Vec4,
+    #[serde(with = "crate::compact")]
+    bounds: Rect,
+    #[serde(with = "crate::compact::vec")]
+    points: Vec<Vec3>,
+    #[serde(with = "crate::compact::option")]
+    target: Option<Vec2>,
+    transform: [f32; 9],
+    normal: Vec3,
+}
+
+#[cfg(test)]
+fn test_scene() -> Scene {
+    Scene {
+        pos: vec2(1.0, 2.0),
+        color: vec4(0.5, 0.25, 1.0, 1.0),
+        bounds: rect(0.0, 0.0, 10.0, 5.0),
+        points: vec![vec3(1.0, 0.0, 0.0), vec3(0.0, 1.5, -2.0)],
+        target: None,
+        transform: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        normal: vec3(0.0, 0.0, 1.0),
+    }
+}
+
+#[test]
+fn test_compact_json() {
+    let s = test_scene();
+    let json = serde_json::to_string(&s).unwrap();
+    assert_eq!(json, concat!(
+        r#"{"pos":[1.0,2.0],"color":[0.5,0.25,1.0,1.0],"bounds":[0.0,0.0,10.0,5.0],"#,
+        r#""points":[[1.0,0.0,0.0],[0.0,1.5,-2.0]],"target":null,"#,
+        r#""transform":[1.0,0.0,0.0,0.0,1.0,0.0,0.0,0.0,1.0],"normal":{"x":0.0,"y":0.0,"z":1.0}}"#,
+    ));
+    assert_eq!(serde_json::from_str::<Scene>(&json).unwrap(), s);
+    let s = Scene { target: Some(vec2(3.0, 4.0)), ..test_scene() };
+    let json = serde_json::to_string(&s).unwrap();
+    assert!(json.contains(r#""target":[3.0,4.0]"#));
+    assert_eq!(serde_json::from_str::<Scene>(&json).unwrap(), s);
+}
+
+#[test]
+fn test_rect_and_lenient_json() {
+    let r = rect(1.0, 2.0, 3.0, 4.0);
+    let json = serde_json::to_string(&r).unwrap();
+    assert_eq!(json, r#"{"xy":{"x":1.0,"y":2.0},"wh":{"x":3.0,"y":4.0}}"#);
+    assert_eq!(serde_json::from_str::<Rect>(&json).unwrap(), r);
+    // the derived impls take the array form too
+    assert_eq!(serde_json::from_str::<Vec3>("[1.0, 2.0, 3.0]").unwrap(), vec3(1.0, 2.0, 3.0));
+    assert_eq!(serde_json::from_str::<Rect>("[[1, 2], [3, 4]]").unwrap(), r);
+    assert!(serde_json::from_str::<Scene>(r#"{"pos":[1.0]}"#).is_err());
+}
+
+#[test]
+fn test_compact_bincode() {
+    let s = Scene { target: Some(vec2(3.0, 4.0)), ..test_scene() };
+    let bytes = bincode::serialize(&s).unwrap();
+    assert_eq!(bincode::deserialize::<Scene>(&bytes).unwrap(), s);
+    // arrays and structs are the same bytes in bincode, no size cost either way
+    assert_eq!(bincode::serialize(&vec3(1.0, 2.0, 3.0)).unwrap(), bincode::serialize(&[1.0f32, 2.0, 3.0]).unwrap());
+    let r = rect(1.0, 2.0, 3.0, 4.0);
+    assert_eq!(bincode::deserialize::<Rect>(&bincode::serialize(&r).unwrap()).unwrap(), r);
+}
@@ -0,0 +1,117 @@
+use crate::*;
+
+// Vec2 as a complex number x + iy. the complex versions of functions that also
+// exist per component (exp, ln, powf, sqrt, recip) get a _complex suffix
+
+impl Vec2 {
+    pub const I: Vec2 = vec2(0.0, 1.0);
+    /// complex conjugate x - iy
+    pub fn conj(&self) -> Vec2 { vec2(self.x, -self.y) }
+    /// |z|, same as norm
+    pub fn modulus(&self) -> f32 { self.x.hypot(self.y) }
+    /// principal argument in (-pi, pi], same as to_angle
+    pub fn arg(&self) -> f32 { self.to_angle() }
+    /// r e^(i theta)
+    pub fn from_polar(r: f32, theta: f32) -> Vec2 { Vec2::from_angle(theta) * r }
+    /// (modulus, arg)
+    pub fn to_polar(&self) -> (f32, f32) { (self.modulus(), self.arg()) }
+    pub fn sqr_complex(&self) -> Vec2 { vec2(self.x * self.x - self.y * self.y, 2.0 * self.x * self.y) }
+    /// 1 / z
+    pub fn recip_complex(&self) -> Vec2 { self.conj() / self.dot(*self) }
+    /// e^z
+    pub fn exp_complex(&self) -> Vec2 { Vec2::from_polar(self.x.exp(), self.y) }
+    /// principal natural log, imaginary part in (-pi, pi]
+    pub fn ln_complex(&self) -> Vec2 { vec2(self.modulus().ln(), self.arg()) }
+    /// principal square root, real part >= 0
+    pub fn sqrt_complex(&self) -> Vec2 {
+        let r = self.modulus();
+        vec2(((r + self.x) * 0.5).sqrt(), ((r - self.x) * 0.5).sqrt().copysign(self.y))
+    }
+    /// principal z^n for real n
+    pub fn powf_complex(&self, n: f32) -> Vec2 {
+        if *self == Vec2::default() { return *self; }
+        let (r, theta) = self.to_polar();
+        Vec2::from_polar(r.powf(n), theta * n)
+    }
+    /// principal z^w for complex w
+    pub fn powc(&self, w: Vec2) -> Vec2 {
+        if *self == Vec2::default() { return *self; }
+        self.ln_complex().mul_complex(w).exp_complex()
+    }
+}
+
+/// iterations of z -> z^2 + c before |z| > 2, None if it stays bounded for max_iter
+pub fn julia_escape(z: Vec2, c: Vec2, max_iter: u32) -> Option<u32> {
+    let mut z = z;
+    for i in 0..max_iter {
+        if z.dot(z) > 4.0 { return Some(i); }
+        z = z.sqr_complex() + c;
+    }
+    None
+}
+
+/// julia_escape starting from z = 0
+pub fn mandelbrot_escape(c: Vec2, max_iter: u32) -> Option<u32> { julia_escape(Vec2::default(), c, max_iter) }
+
+/// continuous escape count for smooth colouring, None inside the set
+pub fn mandelbrot_smooth(c: Vec2, max_iter: u32) -> Option<f32> {
+    // bail out far away so the log log correction is accurate
+    let mut z = Vec2::default();
+    for i in 0..max_iter {
+        let m = z.dot(z);
+        if m > 256.0 * 256.0 { return Some(i as f32 + 1.0 - (0.5 * m.ln()).log2()); }
+        z = z.sqr_complex() + c;
+    }
+    None
+}
+
+#[test]
+fn test_complex_values() {
+    use core::f32::consts::{E, PI, FRAC_PI_2, FRAC_PI_4, SQRT_2};
+    let close = |a: Vec2, b: Vec2| (a - b).norm() < 1e-5;
+    let z = vec2(3.0, 4.0);
+    assert_eq!(z.conj(), vec2(3.0, -4.0));
+    assert_eq!(z.modulus(), 5.0);
+    assert!(close(z.mul_complex(z.recip_complex()), vec2(1.0, 0.0)));
+    assert!(close(z.div_complex(z), vec2(1.0, 0.0)));
+    assert_eq!(Vec2::I.sqr_complex(), vec2(-1.0, 0.0));
+    assert!(close(Vec2::from_polar(SQRT_2, FRAC_PI_4), vec2(1.0, 1.0)));
+    let (r, theta) = vec2(-1.0, 0.0).to_polar();
+    assert!((r - 1.0).abs() < 1e-6 && (theta - PI).abs() < 1e-6);
+    // euler: e^(i pi) = -1
+    assert!(close(vec2(0.0, PI).exp_complex(), vec2(-1.0, 0.0)));
+    assert!(close(vec2(1.0, 0.0).exp_complex(), vec2(E, 0.0)));
+    assert!(close(vec2(-1.0, 0.0).ln_complex(), vec2(0.0, PI)));
+    assert!(close(Vec2::I.ln_complex(), vec2(0.0, FRAC_PI_2)));
+    assert!(close(z.ln_complex().exp_complex(), z));
+    // sqrt(3 + 4i) = 2 + i, sqrt(-4) = 2i, sqrt(-2i) = 1 - i
+    assert!(close(z.sqrt_complex(), vec2(2.0, 1.0)));
+    assert!(close(vec2(-4.0, 0.0).sqrt_complex(), vec2(0.0, 2.0)));
+    assert!(close(vec2(0.0, -2.0).sqrt_complex(), vec2(1.0, -1.0)));
+    assert!(close(z.powf_complex(0.5), z.sqrt_complex()));
+    assert!(close(z.powf_complex(2.0), z.mul_complex(z)));
+    assert!(close(z.powf_complex(-1.0), z.recip_complex()));
+    // i^i = e^(-pi/2)
+    assert!(close(Vec2::I.powc(Vec2::I), vec2((-FRAC_PI_2).exp(), 0.0)));
+    assert!(close(z.powc(vec2(3.0, 0.0)) / 125.0, z.mul_complex(z).mul_complex(z) / 125.0));
+    assert_eq!(Vec2::default().powc(Vec2::I), Vec2::default());
+}
+
+#[test]
+fn test_mandelbrot() {
+    assert_eq!(mandelbrot_escape(vec2(0.0, 0.0), 100), None);
+    assert_eq!(mandelbrot_escape(vec2(-1.0, 0.0), 100), None);
+    assert_eq!(mandelbrot_escape(vec2(-0.75, 0.1), 10), None);
+    // 0, 1, 2, 5: |5| > 2 after three steps
+    assert_eq!(mandelbrot_escape(vec2(1.0, 0.0), 100), Some(3));
+    assert_eq!(mandelbrot_escape(vec2(3.0, 0.0), 100), Some(1));
+    assert_eq!(julia_escape(vec2(3.0, 0.0), vec2(0.0, 0.0), 100), Some(0));
+    assert_eq!(julia_escape(vec2(0.5, 0.0), vec2(0.0, 0.0), 100), None);
+    assert_eq!(mandelbrot_smooth(vec2(-0.1, 0.1), 100), None);
+    // smooth count increases toward the set and is continuous across iteration bands
+    let a = mandelbrot_smooth(vec2(0.5, 0.0), 1000).unwrap();
+    let b = mandelbrot_smooth(vec2(0.3, 0.0), 1000).unwrap();
+    assert!(b > a);
+    let s: Vec<f32> = (0..200).map(|i| mandelbrot_smooth(vec2(1.0 + i as f32 * 0.005, 0.0), 100).unwrap()).collect();
+    assert!(s.windows(2).all(|w| (w[0] - w[1]).abs() < 0.1));
+}
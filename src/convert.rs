@@ -0,0 +1,160 @@
+use crate::*;
+
+// arrays, tuples, slices and iterators. the vectors are repr(C) structs of n f32s, which is laid out
+// exactly like [f32; n], so views between the two are plain pointer casts
+
+macro_rules! impl_convert {
+    (@f32 $f:ident) => { f32 };
+    ($t:ident, $n:literal { $($f:ident),+ }) => {
+        impl $t {
+            pub fn to_array(&self) -> [f32; $n] { [$(self.$f),+] }
+            pub fn as_array(&self) -> &[f32; $n] {
+                // SAFETY: repr(C) with only f32 fields has the layout of [f32; n]
+                unsafe { &*(self as *const $t as *const [f32; $n]) }
+            }
+            pub fn as_array_mut(&mut self) -> &mut [f32; $n] {
+                // SAFETY: as above
+                unsafe { &mut *(self as *mut $t as *mut [f32; $n]) }
+            }
+            /// the components in order x, y, z, w
+            pub fn iter(&self) -> core::slice::Iter<'_, f32> { self.as_array().iter() }
+            pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, f32> { self.as_array_mut().iter_mut() }
+            /// every component of every vector in order, eg for uploading
+            pub fn flatten_slice(vs: &[$t]) -> &[f32] {
+                // SAFETY: each element is n f32s with no padding between elements
+                unsafe { core::slice::from_raw_parts(vs.as_ptr() as *const f32, vs.len() * $n) }
+            }
+            pub fn flatten_slice_mut(vs: &mut [$t]) -> &mut [f32] {
+                // SAFETY: as above
+                unsafe { core::slice::from_raw_parts_mut(vs.as_mut_ptr() as *mut f32, vs.len() * $n) }
+            }
+            /// floats as vectors, None unless the length is a multiple of n
+            pub fn from_flat_slice(fs: &[f32]) -> Option<&[$t]> {
+                if fs.len() % $n != 0 { return None; }
+                // SAFETY: same alignment as f32, and the length was checked above
+                Some(unsafe { core::slice::from_raw_parts(fs.as_ptr() as *const $t, fs.len() / $n) })
+            }
+            pub fn from_flat_slice_mut(fs: &mut [f32]) -> Option<&mut [$t]> {
+                if fs.len() % $n != 0 { return None; }
+                // SAFETY: as above
+                Some(unsafe { core::slice::from_raw_parts_mut(fs.as_mut_ptr() as *mut $t, fs.len() / $n) })
+            }
+        }
+        impl From<[f32; $n]> for $t { fn from(a: [f32; $n]) -> $t { let [$($f),+] = a; $t { $($f),+ } } }
+        impl From<$t> for [f32; $n] { fn from(v: $t) -> [f32; $n] { v.to_array() } }
+        impl From<($(impl_convert!(@f32 $f)),+)> for $t {
+            fn from(t: ($(impl_convert!(@f32 $f)),+)) -> $t { let ($($f),+) = t; $t { $($f),+ } }
+        }
+        impl From<$t> for ($(impl_convert!(@f32 $f)),+) { fn from(v: $t) -> Self { ($(v.$f),+) } }
+        impl AsRef<[f32; $n]> for $t { fn as_ref(&self) -> &[f32; $n] { self.as_array() } }
+        impl AsMut<[f32; $n]> for $t { fn as_mut(&mut self) -> &mut [f32; $n] { self.as_array_mut() } }
+        impl AsRef<[f32]> for $t { fn as_ref(&self) -> &[f32] { self.as_array() } }
+        impl AsMut<[f32]> for $t { fn as_mut(&mut self) -> &mut [f32] { self.as_array_mut() } }
+        /// component by axis number, panics past the last
+        impl core::ops::Index<usize> for $t {
+            type Output = f32;
+            fn index(&self, i: usize) -> &f32 { &self.as_array()[i] }
+        }
+        impl core::ops::IndexMut<usize> for $t {
+            fn index_mut(&mut self, i: usize) -> &mut f32 { &mut self.as_array_mut()[i] }
+        }
+        impl IntoIterator for $t {
+            type Item = f32;
+            type IntoIter = core::array::IntoIter<f32, $n>;
+            fn into_iter(self) -> Self::IntoIter { self.to_array().into_iter() }
+        }
+        impl<'a> IntoIterator for &'a $t {
+            type Item = &'a f32;
+            type IntoIter = core::slice::Iter<'a, f32>;
+            fn into_iter(self) -> Self::IntoIter { self.iter() }
+        }
+        impl<'a> IntoIterator for &'a mut $t {
+            type Item = &'a mut f32;
+            type IntoIter = core::slice::IterMut<'a, f32>;
+            fn into_iter(self) -> Self::IntoIter { self.iter_mut() }
+        }
+        impl core::iter::Sum for $t { fn sum<I: Iterator<Item = $t>>(it: I) -> $t { it.fold($t::splat(0.0), |a, b| a + b) } }
+        impl<'a> core::iter::Sum<&'a $t> for $t { fn sum<I: Iterator<Item = &'a $t>>(it: I) -> $t { it.copied().sum() } }
+        /// component wise
+        impl core::iter::Product for $t { fn product<I: Iterator<Item = $t>>(it: I) -> $t { it.fold($t::splat(1.0), |a, b| a * b) } }
+        impl<'a> core::iter::Product<&'a $t> for $t { fn product<I: Iterator<Item = &'a $t>>(it: I) -> $t { it.copied().product() } }
+    };
+}
+
+impl_convert!(Vec2, 2 { x, y });
+impl_convert!(Vec3, 3 { x, y, z });
+impl_convert!(Vec4, 4 { x, y, z, w });
+
+/// [x, y, w, h]
+impl From<[f32; 4]> for Rect { fn from(a: [f32; 4]) -> Rect { rect(a[0], a[1], a[2], a[3]) } }
+impl From<Rect> for [f32; 4] { fn from(r: Rect) -> [f32; 4] { [r.xy.x, r.xy.y, r.wh.x, r.wh.y] } }
+/// (xy, wh)
+impl From<(Vec2, Vec2)> for Rect { fn from((xy, wh): (Vec2, Vec2)) -> Rect { rectv(xy, wh) } }
+impl From<Rect> for (Vec2, Vec2) { fn from(r: Rect) -> (Vec2, Vec2) { (r.xy, r.wh) } }
+
+#[test]
+fn test_array_tuple_conversions() {
+    let v = vec3(1.0, 2.0, 3.0);
+    assert_eq!(Vec3::from([1.0, 2.0, 3.0]), v);
+    assert_eq!(<[f32; 3]>::from(v), [1.0, 2.0, 3.0]);
+    let t: (f32, f32) = vec2(4.0, 5.0).into();
+    assert_eq!(t, (4.0, 5.0));
+    assert_eq!(Vec2::from((4.0, 5.0)), vec2(4.0, 5.0));
+    assert_eq!(Vec4::from((1.0, 2.0, 3.0, 4.0)).to_array(), [1.0, 2.0, 3.0, 4.0]);
+    let r = rect(1.0, 2.0, 3.0, 4.0);
+    assert_eq!(Rect::from(<[f32; 4]>::from(r)), r);
+    assert_eq!(Rect::from((vec2(1.0, 2.0), vec2(3.0, 4.0))), r);
+    assert_eq!(<(Vec2, Vec2)>::from(r), (r.xy, r.wh));
+}
+
+#[test]
+fn test_index_and_iter() {
+    let mut v = vec4(1.0, 2.0, 3.0, 4.0);
+    assert_eq!((v[0], v[1], v[2], v[3]), (1.0, 2.0, 3.0, 4.0));
+    for axis in 0..4 {
+        v[axis] *= 10.0;
+    }
+    assert_eq!(v, vec4(10.0, 20.0, 30.0, 40.0));
+    assert_eq!(v.iter().sum::<f32>(), 100.0);
+    for c in &mut v {
+        *c += 1.0;
+    }
+    assert_eq!(v.into_iter().collect::<Vec<_>>(), vec![11.0, 21.0, 31.0, 41.0]);
+    assert_eq!((&v).into_iter().count(), 4);
+    let a: &[f32] = v.as_ref();
+    assert_eq!(a, &[11.0, 21.0, 31.0, 41.0]);
+    v.as_array_mut()[1] = 0.0;
+    assert_eq!(v.y, 0.0);
+}
+
+#[test]
+#[should_panic]
+fn test_index_out_of_range() {
+    let v = vec2(1.0, 2.0);
+    let _ = v[2];
+}
+
+#[test]
+fn test_slice_casts() {
+    let mut vs = vec![vec3(1.0, 2.0, 3.0), vec3(4.0, 5.0, 6.0)];
+    assert_eq!(Vec3::flatten_slice(&vs), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    Vec3::flatten_slice_mut(&mut vs)[4] = -5.0;
+    assert_eq!(vs[1].y, -5.0);
+    let fs = [1.0, 2.0, 3.0, 4.0];
+    assert_eq!(Vec2::from_flat_slice(&fs), Some(&[vec2(1.0, 2.0), vec2(3.0, 4.0)][..]));
+    assert_eq!(Vec3::from_flat_slice(&fs), None);
+    assert_eq!(Vec4::from_flat_slice(&[]), Some(&[][..]));
+    let mut fs = fs;
+    Vec4::from_flat_slice_mut(&mut fs).unwrap()[0].w = 0.0;
+    assert_eq!(fs, [1.0, 2.0, 3.0, 0.0]);
+}
+
+#[test]
+fn test_sum_product() {
+    let vs = [vec2(1.0, 2.0), vec2(3.0, 4.0), vec2(5.0, 6.0)];
+    assert_eq!(vs.iter().sum::<Vec2>(), vec2(9.0, 12.0));
+    assert_eq!(vs.into_iter().sum::<Vec2>(), vec2(9.0, 12.0));
+    assert_eq!(vs.iter().product::<Vec2>(), vec2(15.0, 48.0));
+    assert_eq!(core::iter::empty::<Vec3>().sum::<Vec3>(), Vec3::splat(0.0));
+    assert_eq!(core::iter::empty::<Vec3>().product::<Vec3>(), Vec3::splat(1.0));
+}
@@ -0,0 +1,176 @@
+use crate::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorError {
+    pub input: String,
+    pub msg: String,
+}
+impl core::fmt::Display for ColorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "can't parse color {:?}: {}", self.input, self.msg)
+    }
+}
+impl core::error::Error for ColorError {}
+
+/// the css named colors, sorted for binary search
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff), ("antiquewhite", 0xfaebd7), ("aqua", 0x00ffff), ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff), ("beige", 0xf5f5dc), ("bisque", 0xffe4c4), ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd), ("blue", 0x0000ff), ("blueviolet", 0x8a2be2), ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887), ("cadetblue", 0x5f9ea0), ("chartreuse", 0x7fff00), ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50), ("cornflowerblue", 0x6495ed), ("cornsilk", 0xfff8dc), ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff), ("darkblue", 0x00008b), ("darkcyan", 0x008b8b), ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9), ("darkgreen", 0x006400), ("darkgrey", 0xa9a9a9), ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b), ("darkolivegreen", 0x556b2f), ("darkorange", 0xff8c00), ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000), ("darksalmon", 0xe9967a), ("darkseagreen", 0x8fbc8f), ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f), ("darkslategrey", 0x2f4f4f), ("darkturquoise", 0x00ced1), ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493), ("deepskyblue", 0x00bfff), ("dimgray", 0x696969), ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff), ("firebrick", 0xb22222), ("floralwhite", 0xfffaf0), ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff), ("gainsboro", 0xdcdcdc), ("ghostwhite", 0xf8f8ff), ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520), ("gray", 0x808080), ("green", 0x008000), ("greenyellow", 0xadff2f),
+    ("grey", 0x808080), ("honeydew", 0xf0fff0), ("hotpink", 0xff69b4), ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082), ("ivory", 0xfffff0), ("khaki", 0xf0e68c), ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5), ("lawngreen", 0x7cfc00), ("lemonchiffon", 0xfffacd), ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080), ("lightcyan", 0xe0ffff), ("lightgoldenrodyellow", 0xfafad2), ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90), ("lightgrey", 0xd3d3d3), ("lightpink", 0xffb6c1), ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa), ("lightskyblue", 0x87cefa), ("lightslategray", 0x778899), ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de), ("lightyellow", 0xffffe0), ("lime", 0x00ff00), ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6), ("magenta", 0xff00ff), ("maroon", 0x800000), ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd), ("mediumorchid", 0xba55d3), ("mediumpurple", 0x9370db), ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee), ("mediumspringgreen", 0x00fa9a), ("mediumturquoise", 0x48d1cc), ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970), ("mintcream", 0xf5fffa), ("mistyrose", 0xffe4e1), ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead), ("navy", 0x000080), ("oldlace", 0xfdf5e6), ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23), ("orange", 0xffa500), ("orangered", 0xff4500), ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa), ("palegreen", 0x98fb98), ("paleturquoise", 0xafeeee), ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5), ("peachpuff", 0xffdab9), ("peru", 0xcd853f), ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd), ("powderblue", 0xb0e0e6), ("purple", 0x800080), ("rebeccapurple", 0x663399),
+    ("red", 0xff0000), ("rosybrown", 0xbc8f8f), ("royalblue", 0x4169e1), ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072), ("sandybrown", 0xf4a460), ("seagreen", 0x2e8b57), ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d), ("silver", 0xc0c0c0), ("skyblue", 0x87ceeb), ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090), ("slategrey", 0x708090), ("snow", 0xfffafa), ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4), ("tan", 0xd2b48c), ("teal", 0x008080), ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347), ("turquoise", 0x40e0d0), ("violet", 0xee82ee), ("wheat", 0xf5deb3),
+    ("white", 0xffffff), ("whitesmoke", 0xf5f5f5), ("yellow", 0xffff00), ("yellowgreen", 0x9acd32),
+];
+
+fn parse_hex(digits: &str) -> Option<Vec4> {
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) { return None; }
+    let v = u32::from_str_radix(digits, 16).ok()?;
+    // short forms repeat each digit, 0xf -> 0xff
+    let nibble = |i: u32| ((v >> (4 * i)) & 0xf) * 0x11;
+    let rgba = match digits.len() {
+        3 => nibble(2) << 24 | nibble(1) << 16 | nibble(0) << 8 | 0xff,
+        4 => nibble(3) << 24 | nibble(2) << 16 | nibble(1) << 8 | nibble(0),
+        6 => v << 8 | 0xff,
+        8 => v,
+        _ => return None,
+    };
+    Some(Vec4::from_rgba_u32(rgba))
+}
+
+/// a number, or a percentage of full which comes back as a fraction of 1 when full is 100
+fn parse_number(s: &str, full: f32) -> Option<f32> {
+    match s.strip_suffix('%') {
+        Some(p) => p.parse::<f32>().ok().map(|x| x / 100.0),
+        None => s.parse::<f32>().ok().map(|x| x / full),
+    }
+}
+
+fn parse_hue(s: &str) -> Option<f32> {
+    if let Some(x) = s.strip_suffix("deg") { return x.parse().ok(); }
+    if let Some(x) = s.strip_suffix("grad") { return x.parse::<f32>().ok().map(|x| x * 0.9); }
+    if let Some(x) = s.strip_suffix("rad") { return x.parse::<f32>().ok().map(|x| x.to_degrees()); }
+    if let Some(x) = s.strip_suffix("turn") { return x.parse::<f32>().ok().map(|x| x * 360.0); }
+    s.parse().ok()
+}
+
+/// "name(a, b, c, d)" or "name(a b c / d)" to name and the arguments
+fn split_function(s: &str) -> Option<(&str, Vec<&str>)> {
+    let (name, rest) = s.split_once('(')?;
+    let args = rest.strip_suffix(')')?;
+    Some((name.trim(), args.split(|c: char| c == ',' || c == '/' || c.is_whitespace()).filter(|a| !a.is_empty()).collect()))
+}
+
+/// parse a css color: #rgb, #rgba, #rrggbb, #rrggbbaa, rgb(), rgba(), hsl(), hsla(), a named color or
+/// transparent. gives srgb encoded (r, g, b, a) in [0, 1]
+pub fn css_parse_color(s: &str) -> Result<Vec4, ColorError> {
+    let err = |msg: &str| ColorError { input: s.to_string(), msg: msg.to_string() };
+    let t = s.trim().to_ascii_lowercase();
+    if let Some(digits) = t.strip_prefix('#') {
+        return parse_hex(digits).ok_or_else(|| err("expected 3, 4, 6 or 8 hex digits"));
+    }
+    if t == "transparent" {
+        return Ok(Vec4::default());
+    }
+    if let Ok(i) = NAMED_COLORS.binary_search_by(|(name, _)| (*name).cmp(t.as_str())) {
+        return Ok(Vec4::from_rgba_u32(NAMED_COLORS[i].1 << 8 | 0xff));
+    }
+    let (name, args) = split_function(&t).ok_or_else(|| err("unknown color"))?;
+    if args.len() != 3 && args.len() != 4 {
+        return Err(err("expected 3 or 4 arguments"));
+    }
+    let alpha = match args.get(3) {
+        Some(a) => parse_number(a, 1.0).ok_or_else(|| err("bad alpha"))?,
+        None => 1.0,
+    };
+    let c = match name {
+        "rgb" | "rgba" => {
+            let ch = |i: usize| parse_number(args[i], 255.0).ok_or_else(|| err("bad channel"));
+            vec4(ch(0)?, ch(1)?, ch(2)?, alpha)
+        }
+        "hsl" | "hsla" => {
+            let h = parse_hue(args[0]).ok_or_else(|| err("bad hue"))?;
+            let sl = |i: usize| parse_number(args[i], 100.0).ok_or_else(|| err("bad saturation or lightness"));
+            vec4(h, sl(1)?.clamp(0.0, 1.0), sl(2)?.clamp(0.0, 1.0), alpha).hsl_to_rgb()
+        }
+        _ => return Err(err("unknown color function")),
+    };
+    Ok(c.clamp(Vec4::splat(0.0), Vec4::splat(1.0)))
+}
+
+#[test]
+fn test_css_hex() {
+    assert_eq!(css_parse_color("#f00").unwrap(), vec4(1.0, 0.0, 0.0, 1.0));
+    assert_eq!(css_parse_color("#0f08").unwrap().to_rgba8(), [0, 255, 0, 0x88]);
+    assert_eq!(css_parse_color("#336699").unwrap().to_rgba8(), [0x33, 0x66, 0x99, 255]);
+    assert_eq!(css_parse_color(" #33669980 ").unwrap().to_rgba8(), [0x33, 0x66, 0x99, 0x80]);
+    assert_eq!(css_parse_color("#ABCDEF").unwrap().to_hex(), "#abcdef");
+    for hex in ["#000000", "#ffffff", "#123456", "#fedcba98", "#7f7f7f80"] {
+        assert_eq!(css_parse_color(hex).unwrap().to_hex(), hex);
+    }
+    assert!(css_parse_color("#12345").is_err());
+    assert!(css_parse_color("#+12").is_err());
+    assert!(css_parse_color("#ggg").is_err());
+}
+
+#[test]
+fn test_css_functions() {
+    let rgba8 = |s: &str| css_parse_color(s).unwrap().to_rgba8();
+    assert_eq!(rgba8("rgb(255, 128, 0)"), [255, 128, 0, 255]);
+    assert_eq!(rgba8("rgba(255,128,0,0.5)"), [255, 128, 0, 128]);
+    assert_eq!(rgba8("rgb(255 128 0 / 25%)"), [255, 128, 0, 64]);
+    assert_eq!(rgba8("RGB(100%, 50%, 0%)"), [255, 128, 0, 255]);
+    assert_eq!(rgba8("rgb(300, -5, 0)"), [255, 0, 0, 255]);
+    assert_eq!(rgba8("hsl(120, 100%, 50%)"), [0, 255, 0, 255]);
+    assert_eq!(rgba8("hsl(240deg 100% 50% / 0.5)"), [0, 0, 255, 128]);
+    assert_eq!(rgba8("hsla(-120, 100%, 25%, 1)"), [0, 0, 128, 255]);
+    assert_eq!(rgba8("hsl(0.5turn, 100%, 50%)"), [0, 255, 255, 255]);
+    assert!(css_parse_color("rgb(1, 2)").is_err());
+    assert!(css_parse_color("rgb(1, 2, x)").is_err());
+    assert!(css_parse_color("cmyk(1, 2, 3, 4)").is_err());
+    assert!(css_parse_color("rgb(1, 2, 3").is_err());
+    let e = css_parse_color("notacolor").unwrap_err();
+    assert_eq!(e.to_string(), "can't parse color \"notacolor\": unknown color");
+}
+
+#[test]
+fn test_css_named() {
+    assert!(NAMED_COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(css_parse_color("rebeccapurple").unwrap().to_hex(), "#663399");
+    assert_eq!(css_parse_color("CornflowerBlue").unwrap().to_hex(), "#6495ed");
+    assert_eq!(css_parse_color("grey").unwrap(), css_parse_color("gray").unwrap());
+    assert_eq!(css_parse_color("transparent").unwrap(), vec4(0.0, 0.0, 0.0, 0.0));
+    for (name, _) in NAMED_COLORS {
+        assert_eq!(css_parse_color(name).unwrap().w, 1.0);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::*;
+use core::f64::consts::PI;
+
+// Vec2 as complex, forward transform is X[k] = sum x[n] e^(-2 pi i k n / N).
+// the inverse scales by 1 / N so ifft(fft(x)) == x
+
+/// e^(-2 pi i k / n), angle worked out in f64 so big transforms keep their accuracy
+fn twiddle(k: usize, n: usize) -> Vec2 {
+    let a = -2.0 * PI * k as f64 / n as f64;
+    vec2(a.cos() as f32, a.sin() as f32)
+}
+
+/// the naive O(n^2) transform, mostly useful for checking
+pub fn dft(input: &[Vec2]) -> Vec<Vec2> {
+    let n = input.len();
+    (0..n).map(|k| {
+        input.iter().enumerate().fold(Vec2::default(), |acc, (j, x)| acc + x.mul_complex(twiddle(k * j % n, n)))
+    }).collect()
+}
+
+fn fft_radix2(buf: &mut [Vec2]) {
+    let n = buf.len();
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j { buf.swap(i, j); }
+    }
+    let tw: Vec<Vec2> = (0..n / 2).map(|k| twiddle(k, n)).collect();
+    let mut len = 2;
+    while len <= n {
+        let step = n / len;
+        for start in (0..n).step_by(len) {
+            for j in 0..len / 2 {
+                let a = buf[start + j];
+                let b = buf[start + j + len / 2].mul_complex(tw[j * step]);
+                buf[start + j] = a + b;
+                buf[start + j + len / 2] = a - b;
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// bluestein: any length as a convolution of power of two length
+fn fft_bluestein(buf: &mut [Vec2]) {
+    let n = buf.len();
+    let m = (2 * n - 1).next_power_of_two();
+    // e^(-pi i k^2 / n), k^2 taken mod 2n first to keep the angle small
+    let chirp: Vec<Vec2> = (0..n).map(|k| twiddle((k as u64 * k as u64 % (2 * n) as u64) as usize, 2 * n)).collect();
+    let mut a = vec![Vec2::default(); m];
+    let mut b = vec![Vec2::default(); m];
+    for k in 0..n {
+        a[k] = buf[k].mul_complex(chirp[k]);
+        b[k] = chirp[k].conj();
+        if k > 0 { b[m - k] = chirp[k].conj(); }
+    }
+    fft_radix2(&mut a);
+    fft_radix2(&mut b);
+    for (x, y) in a.iter_mut().zip(&b) { *x = x.mul_complex(*y); }
+    ifft(&mut a);
+    for k in 0..n { buf[k] = a[k].mul_complex(chirp[k]); }
+}
+
+/// in place forward transform of any length, radix 2 for powers of two and bluestein otherwise
+pub fn fft(buf: &mut [Vec2]) {
+    match buf.len() {
+        0 | 1 => {}
+        n if n.is_power_of_two() => fft_radix2(buf),
+        _ => fft_bluestein(buf),
+    }
+}
+
+/// in place inverse transform, including the 1 / n
+pub fn ifft(buf: &mut [Vec2]) {
+    let scale = 1.0 / buf.len() as f32;
+    for x in buf.iter_mut() { *x = x.conj(); }
+    fft(buf);
+    for x in buf.iter_mut() { *x = x.conj() * scale; }
+}
+
+/// spectrum of a real signal, the n / 2 + 1 bins up to nyquist. the rest are the conjugates of these
+pub fn fft_real(input: &[f32]) -> Vec<Vec2> {
+    let mut buf: Vec<Vec2> = input.iter().map(|&x| vec2(x, 0.0)).collect();
+    fft(&mut buf);
+    buf.truncate(input.len() / 2 + 1);
+    buf
+}
+
+/// real signal of length n back from the half spectrum fft_real gives. panics if spectrum has fewer than n / 2 + 1 bins
+pub fn ifft_real(spectrum: &[Vec2], n: usize) -> Vec<f32> {
+    assert!(n == 0 || spectrum.len() > n / 2, "ifft_real needs n / 2 + 1 = {} bins for n = {}, got {}", n / 2 + 1, n, spectrum.len());
+    let mut buf = vec![Vec2::default(); n];
+    for k in 0..n {
+        buf[k] = if k < spectrum.len() { spectrum[k] } else { spectrum[n - k].conj() };
+    }
+    ifft(&mut buf);
+    buf.iter().map(|z| z.x).collect()
+}
+
+fn transform2(buf: &mut [Vec2], width: usize, height: usize, f: fn(&mut [Vec2])) {
+    assert_eq!(buf.len(), width * height);
+    if width == 0 || height == 0 { return; }
+    for row in buf.chunks_mut(width) { f(row); }
+    let mut col = vec![Vec2::default(); height];
+    for x in 0..width {
+        for y in 0..height { col[y] = buf[y * width + x]; }
+        f(&mut col);
+        for y in 0..height { buf[y * width + x] = col[y]; }
+    }
+}
+
+/// in place 2d transform of a row major width * height buffer
+pub fn fft2(buf: &mut [Vec2], width: usize, height: usize) { transform2(buf, width, height, fft) }
+/// in place inverse of fft2
+pub fn ifft2(buf: &mut [Vec2], width: usize, height: usize) { transform2(buf, width, height, ifft) }
+
+#[cfg(test)]
+fn test_signal(n: usize) -> Vec<Vec2> {
+    (0..n).map(|i| vec2((i as f32 * 0.37).sin() + 0.5, (i as f32 * 1.3).cos() * 0.25 - i as f32 * 0.01)).collect()
+}
+
+#[cfg(test)]
+fn max_err(a: &[Vec2], b: &[Vec2]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (*x - *y).norm()).fold(0.0, f32::max)
+}
+
+#[test]
+fn test_fft_matches_dft() {
+    for n in [1, 2, 3, 4, 5, 7, 8, 12, 16, 17, 30, 64, 100] {
+        let x = test_signal(n);
+        let mut y = x.clone();
+        fft(&mut y);
+        assert!(max_err(&y, &dft(&x)) < 1e-3 * n as f32, "n = {}", n);
+        ifft(&mut y);
+        assert!(max_err(&y, &x) < 1e-4, "n = {}", n);
+    }
+}
+
+#[test]
+fn test_fft_known_values() {
+    // impulse has a flat spectrum, a constant has everything at dc
+    let mut x = vec![Vec2::default(); 6];
+    x[0] = vec2(1.0, 0.0);
+    fft(&mut x);
+    assert!(x.iter().all(|z| (*z - vec2(1.0, 0.0)).norm() < 1e-6));
+    let mut x = vec![vec2(2.0, 0.0); 8];
+    fft(&mut x);
+    assert!((x[0] - vec2(16.0, 0.0)).norm() < 1e-5);
+    assert!(x[1..].iter().all(|z| z.norm() < 1e-5));
+    // a cosine at bin 3 shows up at bins 3 and n - 3
+    let n = 32;
+    let x: Vec<f32> = (0..n).map(|i| (2.0 * core::f32::consts::PI * 3.0 * i as f32 / n as f32).cos()).collect();
+    let s = fft_real(&x);
+    assert_eq!(s.len(), 17);
+    for (k, z) in s.iter().enumerate() {
+        let expected = if k == 3 { 16.0 } else { 0.0 };
+        assert!((z.norm() - expected).abs() < 1e-4, "bin {}", k);
+    }
+}
+
+#[test]
+fn test_fft_real() {
+    for n in [7, 8, 9] {
+        let x: Vec<f32> = test_signal(n).iter().map(|z| z.x).collect();
+        let full = dft(&x.iter().map(|&r| vec2(r, 0.0)).collect::<Vec<_>>());
+        let half = fft_real(&x);
+        assert_eq!(half.len(), n / 2 + 1);
+        assert!(max_err(&half, &full[..n / 2 + 1]) < 1e-4);
+        let back = ifft_real(&half, n);
+        assert!(back.iter().zip(&x).all(|(a, b)| (a - b).abs() < 1e-5));
+    }
+}
+
+#[test]
+fn test_fft2() {
+    let (w, h) = (6, 4);
+    let x = test_signal(w * h);
+    let mut y = x.clone();
+    fft2(&mut y, w, h);
+    // against the 2d dft written out directly
+    for v in 0..h {
+        for u in 0..w {
+            let mut acc = Vec2::default();
+            for j in 0..h {
+                for i in 0..w {
+                    acc += x[j * w + i].mul_complex(twiddle(u * i % w, w)).mul_complex(twiddle(v * j % h, h));
+                }
+            }
+            assert!((y[v * w + u] - acc).norm() < 1e-4);
+        }
+    }
+    ifft2(&mut y, w, h);
+    assert!(max_err(&y, &x) < 1e-5);
+}
+
+#[test]
+fn test_fft_empty_and_short() {
+    let mut empty: Vec<Vec2> = vec![];
+    fft2(&mut empty, 0, 0);
+    ifft2(&mut empty, 0, 4);
+    assert!(empty.is_empty());
+    assert!(ifft_real(&fft_real(&[]), 0).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "ifft_real needs n / 2 + 1 = 5 bins for n = 8, got 1")]
+fn test_ifft_real_short_spectrum() {
+    ifft_real(&[vec2(1.0, 0.0)], 8);
+}
@@ -0,0 +1,259 @@
+use crate::*;
+
+// writing uniform and storage buffer contents with the glsl std140 / std430
+// padding rules. build a struct field by field with GpuWriter, each push
+// returns the byte offset the field landed at:
+//
+//     let mut w = GpuWriter::new(BufferLayout::Std140);
+//     w.push(&light_pos);    // Vec3 at 0
+//     w.push(&intensity);    // f32 at 12, fills the vec3's padding
+//     w.push(&color);        // Vec4 at 16
+//     let bytes = w.finish();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BufferLayout {
+    /// uniform buffers. arrays and structs round up to 16 byte alignment
+    Std140,
+    /// storage buffers. like std140 without the rounding up
+    Std430,
+}
+
+/// a type that can go in a gpu buffer
+pub trait GpuType {
+    /// alignment as a struct member
+    fn align(layout: BufferLayout) -> usize;
+    /// bytes actually written, vec3 is 12 even though it aligns to 16
+    fn size() -> usize;
+    /// little endian bytes, out is exactly size() long
+    fn write_bytes(&self, out: &mut [u8]);
+}
+
+impl GpuType for f32 {
+    fn align(_: BufferLayout) -> usize { 4 }
+    fn size() -> usize { 4 }
+    fn write_bytes(&self, out: &mut [u8]) { out.copy_from_slice(&self.to_le_bytes()) }
+}
+impl GpuType for i32 {
+    fn align(_: BufferLayout) -> usize { 4 }
+    fn size() -> usize { 4 }
+    fn write_bytes(&self, out: &mut [u8]) { out.copy_from_slice(&self.to_le_bytes()) }
+}
+impl GpuType for u32 {
+    fn align(_: BufferLayout) -> usize { 4 }
+    fn size() -> usize { 4 }
+    fn write_bytes(&self, out: &mut [u8]) { out.copy_from_slice(&self.to_le_bytes()) }
+}
+
+fn write_floats(v: &[f32], out: &mut [u8]) {
+    for (f, chunk) in v.iter().zip(out.chunks_exact_mut(4)) { chunk.copy_from_slice(&f.to_le_bytes()); }
+}
+
+impl GpuType for Vec2 {
+    fn align(_: BufferLayout) -> usize { 8 }
+    fn size() -> usize { 8 }
+    fn write_bytes(&self, out: &mut [u8]) { write_floats(&[self.x, self.y], out) }
+}
+impl GpuType for Vec3 {
+    fn align(_: BufferLayout) -> usize { 16 }
+    fn size() -> usize { 12 }
+    fn write_bytes(&self, out: &mut [u8]) { write_floats(&[self.x, self.y, self.z], out) }
+}
+impl GpuType for Vec4 {
+    fn align(_: BufferLayout) -> usize { 16 }
+    fn size() -> usize { 16 }
+    fn write_bytes(&self, out: &mut [u8]) { write_floats(&[self.x, self.y, self.z, self.w], out) }
+}
+
+fn round_up(x: usize, align: usize) -> usize { x.div_ceil(align) * align }
+
+/// lays out one struct's worth of fields
+#[derive(Debug, Clone)]
+pub struct GpuWriter {
+    pub layout: BufferLayout,
+    bytes: Vec<u8>,
+    /// largest alignment of anything pushed so far
+    max_align: usize,
+}
+
+impl GpuWriter {
+    pub fn new(layout: BufferLayout) -> GpuWriter { GpuWriter { layout, bytes: Vec::new(), max_align: 4 } }
+    /// pad to align then reserve size bytes, giving the offset
+    fn reserve(&mut self, align: usize, size: usize) -> usize {
+        let offset = round_up(self.bytes.len(), align);
+        self.bytes.resize(offset + size, 0);
+        self.max_align = self.max_align.max(align);
+        offset
+    }
+    /// the array element stride and alignment for elements of this alignment and size
+    fn array_stride(&self, align: usize, size: usize) -> (usize, usize) {
+        let align = match self.layout {
+            BufferLayout::Std140 => round_up(align, 16),
+            BufferLayout::Std430 => align,
+        };
+        (round_up(size, align), align)
+    }
+    /// the offset so far, ie where a trailing runtime sized array would start
+    pub fn len(&self) -> usize { self.bytes.len() }
+    pub fn is_empty(&self) -> bool { self.bytes.is_empty() }
+    pub fn push<T: GpuType>(&mut self, v: &T) -> usize {
+        let offset = self.reserve(T::align(self.layout), T::size());
+        v.write_bytes(&mut self.bytes[offset..offset + T::size()]);
+        offset
+    }
+    /// a fixed size array, in std140 every element takes at least 16 bytes
+    pub fn push_array<T: GpuType>(&mut self, vs: &[T]) -> usize {
+        let (stride, align) = self.array_stride(T::align(self.layout), T::size());
+        let offset = self.reserve(align, stride * vs.len());
+        for (i, v) in vs.iter().enumerate() {
+            let at = offset + i * stride;
+            v.write_bytes(&mut self.bytes[at..at + T::size()]);
+        }
+        offset
+    }
+    /// m as three columns of three floats, column major like glsl. each column is padded out to a vec4
+    pub fn push_mat3(&mut self, m: &[f32; 9]) -> usize {
+        self.push_array(&[vec3(m[0], m[1], m[2]), vec3(m[3], m[4], m[5]), vec3(m[6], m[7], m[8])])
+    }
+    /// m as four columns of four floats, column major like glsl
+    pub fn push_mat4(&mut self, m: &[f32; 16]) -> usize {
+        self.push_array(&[
+            vec4(m[0], m[1], m[2], m[3]), vec4(m[4], m[5], m[6], m[7]),
+            vec4(m[8], m[9], m[10], m[11]), vec4(m[12], m[13], m[14], m[15]),
+        ])
+    }
+    fn struct_align(&self) -> usize {
+        match self.layout {
+            BufferLayout::Std140 => round_up(self.max_align, 16),
+            BufferLayout::Std430 => self.max_align,
+        }
+    }
+    /// a nested struct laid out by another writer with the same layout
+    pub fn push_struct(&mut self, s: &GpuWriter) -> usize {
+        let bytes = s.clone().finish();
+        let offset = self.reserve(s.struct_align(), bytes.len());
+        self.bytes[offset..].copy_from_slice(&bytes);
+        offset
+    }
+    /// an array of structs, all laid out the same way
+    pub fn push_struct_array(&mut self, ss: &[GpuWriter]) -> usize {
+        let align = ss.iter().map(|s| s.struct_align()).max().unwrap_or(4);
+        let offset = round_up(self.bytes.len(), align);
+        for s in ss { self.push_struct(s); }
+        offset
+    }
+    /// the bytes, padded out to a multiple of the struct's alignment
+    pub fn finish(mut self) -> Vec<u8> {
+        let len = round_up(self.bytes.len(), self.struct_align());
+        self.bytes.resize(len, 0);
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+fn read_f32(b: &[u8], offset: usize) -> f32 { f32::from_le_bytes(b[offset..offset + 4].try_into().unwrap()) }
+
+#[test]
+fn test_gpu_scalars_and_vectors() {
+    for layout in [BufferLayout::Std140, BufferLayout::Std430] {
+        let mut w = GpuWriter::new(layout);
+        assert_eq!(w.push(&1.0f32), 0);
+        assert_eq!(w.push(&vec2(2.0, 3.0)), 8);
+        assert_eq!(w.push(&vec3(4.0, 5.0, 6.0)), 16);
+        // a scalar slots into the end of a vec3
+        assert_eq!(w.push(&7u32), 28);
+        assert_eq!(w.push(&vec4(8.0, 9.0, 10.0, 11.0)), 32);
+        assert_eq!(w.push(&-1i32), 48);
+        assert_eq!(w.push(&vec3(0.0, 0.0, 0.0)), 64);
+        let b = w.finish();
+        assert_eq!(b.len(), 80);
+        assert_eq!(read_f32(&b, 8), 2.0);
+        assert_eq!(read_f32(&b, 24), 6.0);
+        assert_eq!(u32::from_le_bytes(b[28..32].try_into().unwrap()), 7);
+        assert_eq!(read_f32(&b, 44), 11.0);
+        assert_eq!(&b[4..8], &[0, 0, 0, 0]);
+    }
+    // a lone float is a 4 byte struct in std430 but rounds up to 16 in std140
+    let mut w = GpuWriter::new(BufferLayout::Std430);
+    w.push(&1.0f32);
+    assert_eq!(w.finish().len(), 4);
+    let mut w = GpuWriter::new(BufferLayout::Std140);
+    w.push(&1.0f32);
+    assert_eq!(w.finish().len(), 16);
+}
+
+#[test]
+fn test_gpu_arrays() {
+    // float[3] then a float: std140 strides 16, std430 strides 4
+    let mut w = GpuWriter::new(BufferLayout::Std140);
+    assert_eq!(w.push(&0.5f32), 0);
+    assert_eq!(w.push_array(&[1.0f32, 2.0, 3.0]), 16);
+    assert_eq!(w.push(&4.0f32), 64);
+    let b = w.finish();
+    assert_eq!((read_f32(&b, 16), read_f32(&b, 32), read_f32(&b, 48)), (1.0, 2.0, 3.0));
+    let mut w = GpuWriter::new(BufferLayout::Std430);
+    assert_eq!(w.push(&0.5f32), 0);
+    assert_eq!(w.push_array(&[1.0f32, 2.0, 3.0]), 4);
+    assert_eq!(w.push(&4.0f32), 16);
+    // vec3 arrays stride 16 in both
+    for layout in [BufferLayout::Std140, BufferLayout::Std430] {
+        let mut w = GpuWriter::new(layout);
+        assert_eq!(w.push_array(&[Vec3::splat(1.0), Vec3::splat(2.0)]), 0);
+        assert_eq!(w.push(&3.0f32), 32);
+    }
+    // vec2 arrays stride 16 in std140, 8 in std430
+    let mut w = GpuWriter::new(BufferLayout::Std140);
+    w.push_array(&[vec2(1.0, 2.0), vec2(3.0, 4.0)]);
+    assert_eq!(read_f32(&w.clone().finish(), 16), 3.0);
+    let mut w = GpuWriter::new(BufferLayout::Std430);
+    w.push_array(&[vec2(1.0, 2.0), vec2(3.0, 4.0)]);
+    assert_eq!(read_f32(&w.finish(), 8), 3.0);
+}
+
+#[test]
+fn test_gpu_matrices_and_structs() {
+    let m3 = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+    let m4: [f32; 16] = core::array::from_fn(|i| i as f32);
+    for layout in [BufferLayout::Std140, BufferLayout::Std430] {
+        let mut w = GpuWriter::new(layout);
+        assert_eq!(w.push(&1.0f32), 0);
+        assert_eq!(w.push_mat3(&m3), 16);
+        assert_eq!(w.push_mat4(&m4), 64);
+        assert_eq!(w.push(&1.0f32), 128);
+        let b = w.finish();
+        // columns padded to 16 bytes
+        assert_eq!((read_f32(&b, 16), read_f32(&b, 32), read_f32(&b, 56)), (1.0, 4.0, 9.0));
+        assert_eq!(read_f32(&b, 64 + 4 * 13), 13.0);
+    }
+    // struct { vec2 a; float b; } inside struct { float x; S s; float y; }
+    let mut inner = GpuWriter::new(BufferLayout::Std140);
+    inner.push(&vec2(1.0, 2.0));
+    inner.push(&3.0f32);
+    let mut w = GpuWriter::new(BufferLayout::Std140);
+    w.push(&0.0f32);
+    assert_eq!(w.push_struct(&inner), 16);
+    assert_eq!(w.push(&4.0f32), 32);
+    assert_eq!(read_f32(&w.clone().finish(), 24), 3.0);
+    let mut inner = GpuWriter::new(BufferLayout::Std430);
+    inner.push(&vec2(1.0, 2.0));
+    inner.push(&3.0f32);
+    let mut w = GpuWriter::new(BufferLayout::Std430);
+    w.push(&0.0f32);
+    assert_eq!(w.push_struct(&inner), 8);
+    assert_eq!(w.push(&4.0f32), 24);
+    assert_eq!(w.push_struct_array(&[inner.clone(), inner.clone()]), 32);
+    assert_eq!(w.len(), 64);
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn test_bytemuck() {
+    let v = [vec3(1.0, 2.0, 3.0), vec3(4.0, 5.0, 6.0)];
+    let bytes: &[u8] = bytemuck::cast_slice(&v);
+    assert_eq!(bytes.len(), 24);
+    assert_eq!(read_f32(bytes, 12), 4.0);
+    let r: Rect = bytemuck::cast([1.0f32, 2.0, 3.0, 4.0]);
+    assert_eq!(r, rect(1.0, 2.0, 3.0, 4.0));
+    assert_eq!(bytemuck::cast::<Vec4, [f32; 4]>(vec4(1.0, 2.0, 3.0, 4.0)), [1.0, 2.0, 3.0, 4.0]);
+    let z: Vec2 = bytemuck::Zeroable::zeroed();
+    assert_eq!(z, Vec2::default());
+}
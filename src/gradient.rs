@@ -0,0 +1,212 @@
+use crate::*;
+use core::f32::consts::TAU;
+
+// gradients and palettes. colors going in and coming out are srgb encoded
+// (r, g, b, a) like the ones css_parse_color gives; the space only changes
+// how stops are mixed
+
+/// what to mix gradient stops in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GradientSpace {
+    /// straight on the encoded values, what most image editors do
+    Srgb,
+    /// physically correct light mixing, brighter in the middle
+    LinearRgb,
+    /// perceptually even steps
+    Oklab,
+    /// hue, saturation and value, going round the shorter way
+    Hsv,
+}
+
+/// what a gradient does with t outside [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Extend {
+    Clamp,
+    Repeat,
+    Mirror,
+}
+
+impl Extend {
+    /// t brought into [0, 1]
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Extend::Clamp => t.clamp(0.0, 1.0),
+            Extend::Repeat => t.rem_euclid(1.0),
+            Extend::Mirror => { let m = t.rem_euclid(2.0); if m > 1.0 { 2.0 - m } else { m } }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    /// (t, color) sorted by t
+    pub stops: Vec<(f32, Vec4)>,
+    pub space: GradientSpace,
+    pub extend: Extend,
+}
+
+fn mix_colors(a: Vec4, b: Vec4, t: f32, space: GradientSpace) -> Vec4 {
+    match space {
+        GradientSpace::Srgb => a.lerp_to(b, t),
+        GradientSpace::LinearRgb => a.srgb_to_linear().lerp_to(b.srgb_to_linear(), t).linear_to_srgb(),
+        GradientSpace::Oklab => {
+            let (a, b) = (a.srgb_to_linear().linear_to_oklab(), b.srgb_to_linear().linear_to_oklab());
+            a.lerp_to(b, t).oklab_to_linear().linear_to_srgb()
+        }
+        GradientSpace::Hsv => {
+            let (mut a, mut b) = (a.rgb_to_hsv(), b.rgb_to_hsv());
+            // greys have no hue of their own, borrow the other end's
+            if a.y == 0.0 { a.x = b.x; }
+            if b.y == 0.0 { b.x = a.x; }
+            let dh = (b.x - a.x + 180.0).rem_euclid(360.0) - 180.0;
+            let mut c = a.lerp_to(b, t);
+            c.x = a.x + dh * t;
+            c.hsv_to_rgb()
+        }
+    }
+}
+
+impl Gradient {
+    /// stops get sorted by t. mixes in oklab and clamps by default
+    pub fn new(stops: &[(f32, Vec4)]) -> Gradient {
+        let mut stops = stops.to_vec();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Gradient { stops, space: GradientSpace::Oklab, extend: Extend::Clamp }
+    }
+    /// colors spread evenly from t = 0 to t = 1
+    pub fn even(colors: &[Vec4]) -> Gradient {
+        let n = colors.len().max(2) - 1;
+        let stops: Vec<(f32, Vec4)> = colors.iter().enumerate().map(|(i, c)| (i as f32 / n as f32, *c)).collect();
+        Gradient::new(&stops)
+    }
+    pub fn with_space(mut self, space: GradientSpace) -> Gradient { self.space = space; self }
+    pub fn with_extend(mut self, extend: Extend) -> Gradient { self.extend = extend; self }
+    /// color at t, transparent black if there are no stops
+    pub fn sample(&self, t: f32) -> Vec4 {
+        let t = self.extend.apply(t);
+        let i = self.stops.partition_point(|s| s.0 <= t);
+        match (self.stops.get(i.wrapping_sub(1)), self.stops.get(i)) {
+            (None, None) => Vec4::default(),
+            (Some(a), None) => a.1,
+            (None, Some(b)) => b.1,
+            (Some(a), Some(b)) => mix_colors(a.1, b.1, (t - a.0) / (b.0 - a.0), self.space),
+        }
+    }
+    /// n evenly spaced samples from t = 0 to t = 1 inclusive
+    pub fn lut(&self, n: usize) -> Vec<Vec4> {
+        (0..n).map(|i| self.sample(if n > 1 { i as f32 / (n - 1) as f32 } else { 0.0 })).collect()
+    }
+
+    fn from_hex(hex: &[u32]) -> Gradient {
+        Gradient::even(&hex.iter().map(|h| Vec4::from_rgba_u32(h << 8 | 0xff)).collect::<Vec<_>>())
+    }
+    // the matplotlib colormaps, from nine evenly spaced samples of each mixed in oklab.
+    // within a couple of 8 bit steps of the real tables
+    pub fn viridis() -> Gradient {
+        Gradient::from_hex(&[0x440154, 0x472d7b, 0x3b528b, 0x2c728e, 0x21918c, 0x28ae80, 0x5ec962, 0xaddc30, 0xfde725])
+    }
+    pub fn magma() -> Gradient {
+        Gradient::from_hex(&[0x000004, 0x1c1044, 0x51127c, 0x832681, 0xb73779, 0xe75263, 0xfc8961, 0xfec488, 0xfcfdbf])
+    }
+    pub fn inferno() -> Gradient {
+        Gradient::from_hex(&[0x000004, 0x210c4a, 0x57106e, 0x8a226a, 0xbc3754, 0xe45a31, 0xf98e09, 0xf8c932, 0xfcffa4])
+    }
+    pub fn plasma() -> Gradient {
+        Gradient::from_hex(&[0x0d0887, 0x4c02a1, 0x7e03a8, 0xa92395, 0xcc4778, 0xe66c5c, 0xf89540, 0xfdc527, 0xf0f921])
+    }
+}
+
+/// a + b cos(2 pi (c t + d)), the cosine palettes from iquilezles.org. opaque
+pub fn cosine_palette(t: f32, a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> Vec4 {
+    (a + b * ((c * t + d) * TAU).cos()).extend(1.0)
+}
+
+/// n opaque colors with evenly spaced hues at fixed saturation and value
+pub fn hsv_palette(n: usize, saturation: f32, value: f32) -> Vec<Vec4> {
+    (0..n).map(|i| vec4(360.0 * i as f32 / n as f32, saturation, value, 1.0).hsv_to_rgb()).collect()
+}
+
+/// n opaque colors with evenly spaced hues in oklch, so they all look equally light.
+/// lightness in [0, 1], chroma around 0.1 stays inside srgb for most hues; the rest get clamped
+pub fn oklch_palette(n: usize, lightness: f32, chroma: f32) -> Vec<Vec4> {
+    (0..n).map(|i| {
+        let c = vec4(lightness, chroma, 360.0 * i as f32 / n as f32, 1.0).oklch_to_oklab().oklab_to_linear();
+        c.clamp(Vec4::splat(0.0), Vec4::splat(1.0)).linear_to_srgb()
+    }).collect()
+}
+
+#[test]
+fn test_gradient_sample() {
+    let black = vec4(0.0, 0.0, 0.0, 1.0);
+    let white = vec4(1.0, 1.0, 1.0, 1.0);
+    let g = Gradient::new(&[(1.0, white), (0.0, black)]).with_space(GradientSpace::Srgb);
+    assert_eq!(g.stops[0].1, black);
+    assert_eq!(g.sample(0.0), black);
+    assert_eq!(g.sample(1.0), white);
+    assert_eq!(g.sample(0.25), vec4(0.25, 0.25, 0.25, 1.0));
+    assert_eq!(g.sample(-1.0), black);
+    assert_eq!(g.sample(7.0), white);
+    // linear light mixing is brighter half way, oklab lands near perceptual mid grey
+    let lin = g.clone().with_space(GradientSpace::LinearRgb).sample(0.5);
+    assert!((lin.x - linear_to_srgb(0.5)).abs() < 1e-5);
+    let ok = g.clone().with_space(GradientSpace::Oklab).sample(0.5);
+    assert!((ok.srgb_to_linear().linear_to_oklab().x - 0.5).abs() < 1e-3);
+    assert!(ok.x < lin.x && ok.x > 0.25);
+    // stops partway along hold their color out to the ends
+    let g = Gradient::new(&[(0.25, black), (0.75, white)]).with_space(GradientSpace::Srgb);
+    assert_eq!(g.sample(0.1), black);
+    assert_eq!(g.sample(0.5), vec4(0.5, 0.5, 0.5, 1.0));
+    assert_eq!(g.sample(0.9), white);
+    assert_eq!(Gradient::new(&[]).sample(0.5), Vec4::default());
+    assert_eq!(Gradient::even(&[white]).sample(0.5), white);
+}
+
+#[test]
+fn test_gradient_hsv_and_extend() {
+    // red to magenta goes backwards through 330, not forwards through green
+    let g = Gradient::even(&[vec4(1.0, 0.0, 0.0, 1.0), vec4(1.0, 0.0, 1.0, 1.0)]).with_space(GradientSpace::Hsv);
+    let mid = g.sample(0.5).rgb_to_hsv();
+    assert!((mid.x - 330.0).abs() < 1e-3);
+    // grey to blue keeps blue's hue
+    let g = Gradient::even(&[vec4(0.5, 0.5, 0.5, 1.0), vec4(0.0, 0.0, 1.0, 1.0)]).with_space(GradientSpace::Hsv);
+    assert!((g.sample(0.5).rgb_to_hsv().x - 240.0).abs() < 1e-3);
+    assert_eq!(Extend::Clamp.apply(1.5), 1.0);
+    assert_eq!(Extend::Repeat.apply(1.25), 0.25);
+    assert_eq!(Extend::Repeat.apply(-0.25), 0.75);
+    assert_eq!(Extend::Mirror.apply(1.25), 0.75);
+    assert_eq!(Extend::Mirror.apply(-0.25), 0.25);
+    let g = Gradient::even(&[vec4(0.0, 0.0, 0.0, 0.0), vec4(1.0, 1.0, 1.0, 1.0)]).with_space(GradientSpace::Srgb).with_extend(Extend::Mirror);
+    assert_eq!(g.sample(1.75), vec4(0.25, 0.25, 0.25, 0.25));
+    let lut = g.lut(5);
+    assert_eq!(lut.len(), 5);
+    assert_eq!(lut[1], vec4(0.25, 0.25, 0.25, 0.25));
+    assert_eq!(lut[4], vec4(1.0, 1.0, 1.0, 1.0));
+}
+
+#[test]
+fn test_palettes() {
+    // the classic rainbow-ish one
+    let h = Vec3::splat(0.5);
+    let c = cosine_palette(0.0, h, h, Vec3::splat(1.0), vec3(0.0, 0.33, 0.67));
+    assert!((c.x - 1.0).abs() < 1e-6 && c.w == 1.0);
+    let p = hsv_palette(3, 1.0, 1.0);
+    assert_eq!(p[0].to_rgba8(), [255, 0, 0, 255]);
+    assert_eq!(p[1].to_rgba8(), [0, 255, 0, 255]);
+    assert_eq!(p[2].to_rgba8(), [0, 0, 255, 255]);
+    let p = oklch_palette(6, 0.7, 0.1);
+    for c in &p {
+        let lab = c.srgb_to_linear().linear_to_oklab();
+        assert!((lab.x - 0.7).abs() < 1e-3);
+    }
+    let hues: Vec<f32> = p.iter().map(|c| c.srgb_to_linear().linear_to_oklab().oklab_to_oklch().z).collect();
+    assert!(hues.windows(2).all(|w| (w[1] - w[0] - 60.0).abs() < 0.5));
+    // colormaps hit their published end and middle values and get lighter as they go
+    let v = Gradient::viridis();
+    assert_eq!(v.sample(0.0).to_hex(), "#440154");
+    assert_eq!(v.sample(0.5).to_hex(), "#21918c");
+    assert_eq!(v.sample(1.0).to_hex(), "#fde725");
+    for g in [Gradient::viridis(), Gradient::magma(), Gradient::inferno(), Gradient::plasma()] {
+        let l: Vec<f32> = g.lut(32).iter().map(|c| c.srgb_to_linear().linear_to_oklab().x).collect();
+        assert!(l.windows(2).all(|w| w[1] > w[0]));
+    }
+}
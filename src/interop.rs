@@ -0,0 +1,221 @@
+// conversions to and from the other math crates, each behind the feature of the same name.
+// vectors convert with From both ways. the matrices here are bare arrays so they get functions instead:
+// mat3 arrays are row major (as mat3_trans_homog reads them), mat4 arrays column major (as mat4_trans_homog
+// and glsl read them), and the other crates are all column major underneath, so only mat3 gets transposed.
+// quaternions are Vec4 xyzw. none of these crates has a rect type
+#![allow(unused_imports)]
+use crate::*;
+
+#[cfg(feature = "glam")]
+mod glam_impls {
+    use crate::*;
+    impl From<glam::Vec2> for Vec2 { fn from(v: glam::Vec2) -> Vec2 { vec2(v.x, v.y) } }
+    impl From<Vec2> for glam::Vec2 { fn from(v: Vec2) -> glam::Vec2 { glam::Vec2::new(v.x, v.y) } }
+    impl From<glam::Vec3> for Vec3 { fn from(v: glam::Vec3) -> Vec3 { vec3(v.x, v.y, v.z) } }
+    impl From<Vec3> for glam::Vec3 { fn from(v: Vec3) -> glam::Vec3 { glam::Vec3::new(v.x, v.y, v.z) } }
+    impl From<glam::Vec3A> for Vec3 { fn from(v: glam::Vec3A) -> Vec3 { vec3(v.x, v.y, v.z) } }
+    impl From<Vec3> for glam::Vec3A { fn from(v: Vec3) -> glam::Vec3A { glam::Vec3A::new(v.x, v.y, v.z) } }
+    impl From<glam::Vec4> for Vec4 { fn from(v: glam::Vec4) -> Vec4 { vec4(v.x, v.y, v.z, v.w) } }
+    impl From<Vec4> for glam::Vec4 { fn from(v: Vec4) -> glam::Vec4 { glam::Vec4::new(v.x, v.y, v.z, v.w) } }
+    impl From<glam::Quat> for Vec4 { fn from(q: glam::Quat) -> Vec4 { vec4(q.x, q.y, q.z, q.w) } }
+    /// no normalization, from_xyzw
+    impl From<Vec4> for glam::Quat { fn from(v: Vec4) -> glam::Quat { glam::Quat::from_xyzw(v.x, v.y, v.z, v.w) } }
+}
+
+#[cfg(feature = "glam")]
+pub fn mat3_to_glam(m: &[f32; 9]) -> glam::Mat3 { glam::Mat3::from_cols_array(m).transpose() }
+#[cfg(feature = "glam")]
+pub fn mat3_from_glam(m: glam::Mat3) -> [f32; 9] { m.transpose().to_cols_array() }
+#[cfg(feature = "glam")]
+pub fn mat4_to_glam(m: &[f32; 16]) -> glam::Mat4 { glam::Mat4::from_cols_array(m) }
+#[cfg(feature = "glam")]
+pub fn mat4_from_glam(m: glam::Mat4) -> [f32; 16] { m.to_cols_array() }
+
+#[cfg(feature = "mint")]
+mod mint_impls {
+    use crate::*;
+    impl From<mint::Vector2<f32>> for Vec2 { fn from(v: mint::Vector2<f32>) -> Vec2 { vec2(v.x, v.y) } }
+    impl From<Vec2> for mint::Vector2<f32> { fn from(v: Vec2) -> mint::Vector2<f32> { mint::Vector2 { x: v.x, y: v.y } } }
+    impl From<mint::Point2<f32>> for Vec2 { fn from(v: mint::Point2<f32>) -> Vec2 { vec2(v.x, v.y) } }
+    impl From<Vec2> for mint::Point2<f32> { fn from(v: Vec2) -> mint::Point2<f32> { mint::Point2 { x: v.x, y: v.y } } }
+    impl From<mint::Vector3<f32>> for Vec3 { fn from(v: mint::Vector3<f32>) -> Vec3 { vec3(v.x, v.y, v.z) } }
+    impl From<Vec3> for mint::Vector3<f32> { fn from(v: Vec3) -> mint::Vector3<f32> { mint::Vector3 { x: v.x, y: v.y, z: v.z } } }
+    impl From<mint::Point3<f32>> for Vec3 { fn from(v: mint::Point3<f32>) -> Vec3 { vec3(v.x, v.y, v.z) } }
+    impl From<Vec3> for mint::Point3<f32> { fn from(v: Vec3) -> mint::Point3<f32> { mint::Point3 { x: v.x, y: v.y, z: v.z } } }
+    impl From<mint::Vector4<f32>> for Vec4 { fn from(v: mint::Vector4<f32>) -> Vec4 { vec4(v.x, v.y, v.z, v.w) } }
+    impl From<Vec4> for mint::Vector4<f32> { fn from(v: Vec4) -> mint::Vector4<f32> { mint::Vector4 { x: v.x, y: v.y, z: v.z, w: v.w } } }
+    impl From<mint::Quaternion<f32>> for Vec4 { fn from(q: mint::Quaternion<f32>) -> Vec4 { vec4(q.v.x, q.v.y, q.v.z, q.s) } }
+    impl From<Vec4> for mint::Quaternion<f32> { fn from(v: Vec4) -> mint::Quaternion<f32> { mint::Quaternion { v: v.xyz().into(), s: v.w } } }
+}
+
+#[cfg(feature = "mint")]
+pub fn mat3_to_mint(m: &[f32; 9]) -> mint::ColumnMatrix3<f32> {
+    mint::ColumnMatrix3 { x: vec3(m[0], m[3], m[6]).into(), y: vec3(m[1], m[4], m[7]).into(), z: vec3(m[2], m[5], m[8]).into() }
+}
+#[cfg(feature = "mint")]
+pub fn mat3_from_mint(m: mint::ColumnMatrix3<f32>) -> [f32; 9] {
+    [m.x.x, m.y.x, m.z.x, m.x.y, m.y.y, m.z.y, m.x.z, m.y.z, m.z.z]
+}
+#[cfg(feature = "mint")]
+pub fn mat4_to_mint(m: &[f32; 16]) -> mint::ColumnMatrix4<f32> {
+    let col = |i: usize| mint::Vector4 { x: m[4 * i], y: m[4 * i + 1], z: m[4 * i + 2], w: m[4 * i + 3] };
+    mint::ColumnMatrix4 { x: col(0), y: col(1), z: col(2), w: col(3) }
+}
+#[cfg(feature = "mint")]
+pub fn mat4_from_mint(m: mint::ColumnMatrix4<f32>) -> [f32; 16] {
+    let [a, b, c, d] = [m.x, m.y, m.z, m.w];
+    [a.x, a.y, a.z, a.w, b.x, b.y, b.z, b.w, c.x, c.y, c.z, c.w, d.x, d.y, d.z, d.w]
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impls {
+    use crate::*;
+    impl From<nalgebra::Vector2<f32>> for Vec2 { fn from(v: nalgebra::Vector2<f32>) -> Vec2 { vec2(v.x, v.y) } }
+    impl From<Vec2> for nalgebra::Vector2<f32> { fn from(v: Vec2) -> nalgebra::Vector2<f32> { nalgebra::Vector2::new(v.x, v.y) } }
+    impl From<nalgebra::Point2<f32>> for Vec2 { fn from(v: nalgebra::Point2<f32>) -> Vec2 { vec2(v.x, v.y) } }
+    impl From<Vec2> for nalgebra::Point2<f32> { fn from(v: Vec2) -> nalgebra::Point2<f32> { nalgebra::Point2::new(v.x, v.y) } }
+    impl From<nalgebra::Vector3<f32>> for Vec3 { fn from(v: nalgebra::Vector3<f32>) -> Vec3 { vec3(v.x, v.y, v.z) } }
+    impl From<Vec3> for nalgebra::Vector3<f32> { fn from(v: Vec3) -> nalgebra::Vector3<f32> { nalgebra::Vector3::new(v.x, v.y, v.z) } }
+    impl From<nalgebra::Point3<f32>> for Vec3 { fn from(v: nalgebra::Point3<f32>) -> Vec3 { vec3(v.x, v.y, v.z) } }
+    impl From<Vec3> for nalgebra::Point3<f32> { fn from(v: Vec3) -> nalgebra::Point3<f32> { nalgebra::Point3::new(v.x, v.y, v.z) } }
+    impl From<nalgebra::Vector4<f32>> for Vec4 { fn from(v: nalgebra::Vector4<f32>) -> Vec4 { vec4(v.x, v.y, v.z, v.w) } }
+    impl From<Vec4> for nalgebra::Vector4<f32> { fn from(v: Vec4) -> nalgebra::Vector4<f32> { nalgebra::Vector4::new(v.x, v.y, v.z, v.w) } }
+    /// coords are stored ijkw, the same order as xyzw
+    impl From<nalgebra::Quaternion<f32>> for Vec4 { fn from(q: nalgebra::Quaternion<f32>) -> Vec4 { q.coords.into() } }
+    impl From<Vec4> for nalgebra::Quaternion<f32> { fn from(v: Vec4) -> nalgebra::Quaternion<f32> { nalgebra::Quaternion::new(v.w, v.x, v.y, v.z) } }
+}
+
+#[cfg(feature = "nalgebra")]
+pub fn mat3_to_nalgebra(m: &[f32; 9]) -> nalgebra::Matrix3<f32> { nalgebra::Matrix3::from_row_slice(m) }
+#[cfg(feature = "nalgebra")]
+pub fn mat3_from_nalgebra(m: nalgebra::Matrix3<f32>) -> [f32; 9] { core::array::from_fn(|i| m[(i / 3, i % 3)]) }
+#[cfg(feature = "nalgebra")]
+pub fn mat4_to_nalgebra(m: &[f32; 16]) -> nalgebra::Matrix4<f32> { nalgebra::Matrix4::from_column_slice(m) }
+#[cfg(feature = "nalgebra")]
+pub fn mat4_from_nalgebra(m: nalgebra::Matrix4<f32>) -> [f32; 16] { core::array::from_fn(|i| m[(i % 4, i / 4)]) }
+
+#[cfg(feature = "cgmath")]
+mod cgmath_impls {
+    use crate::*;
+    impl From<cgmath::Vector2<f32>> for Vec2 { fn from(v: cgmath::Vector2<f32>) -> Vec2 { vec2(v.x, v.y) } }
+    impl From<Vec2> for cgmath::Vector2<f32> { fn from(v: Vec2) -> cgmath::Vector2<f32> { cgmath::Vector2::new(v.x, v.y) } }
+    impl From<cgmath::Point2<f32>> for Vec2 { fn from(v: cgmath::Point2<f32>) -> Vec2 { vec2(v.x, v.y) } }
+    impl From<Vec2> for cgmath::Point2<f32> { fn from(v: Vec2) -> cgmath::Point2<f32> { cgmath::Point2::new(v.x, v.y) } }
+    impl From<cgmath::Vector3<f32>> for Vec3 { fn from(v: cgmath::Vector3<f32>) -> Vec3 { vec3(v.x, v.y, v.z) } }
+    impl From<Vec3> for cgmath::Vector3<f32> { fn from(v: Vec3) -> cgmath::Vector3<f32> { cgmath::Vector3::new(v.x, v.y, v.z) } }
+    impl From<cgmath::Point3<f32>> for Vec3 { fn from(v: cgmath::Point3<f32>) -> Vec3 { vec3(v.x, v.y, v.z) } }
+    impl From<Vec3> for cgmath::Point3<f32> { fn from(v: Vec3) -> cgmath::Point3<f32> { cgmath::Point3::new(v.x, v.y, v.z) } }
+    impl From<cgmath::Vector4<f32>> for Vec4 { fn from(v: cgmath::Vector4<f32>) -> Vec4 { vec4(v.x, v.y, v.z, v.w) } }
+    impl From<Vec4> for cgmath::Vector4<f32> { fn from(v: Vec4) -> cgmath::Vector4<f32> { cgmath::Vector4::new(v.x, v.y, v.z, v.w) } }
+    impl From<cgmath::Quaternion<f32>> for Vec4 { fn from(q: cgmath::Quaternion<f32>) -> Vec4 { vec4(q.v.x, q.v.y, q.v.z, q.s) } }
+    impl From<Vec4> for cgmath::Quaternion<f32> { fn from(v: Vec4) -> cgmath::Quaternion<f32> { cgmath::Quaternion::new(v.w, v.x, v.y, v.z) } }
+}
+
+#[cfg(feature = "cgmath")]
+pub fn mat3_to_cgmath(m: &[f32; 9]) -> cgmath::Matrix3<f32> {
+    cgmath::Matrix3::new(m[0], m[3], m[6], m[1], m[4], m[7], m[2], m[5], m[8])
+}
+#[cfg(feature = "cgmath")]
+pub fn mat3_from_cgmath(m: cgmath::Matrix3<f32>) -> [f32; 9] { core::array::from_fn(|i| m[i % 3][i / 3]) }
+#[cfg(feature = "cgmath")]
+pub fn mat4_to_cgmath(m: &[f32; 16]) -> cgmath::Matrix4<f32> {
+    cgmath::Matrix4::from([[m[0], m[1], m[2], m[3]], [m[4], m[5], m[6], m[7]], [m[8], m[9], m[10], m[11]], [m[12], m[13], m[14], m[15]]])
+}
+#[cfg(feature = "cgmath")]
+pub fn mat4_from_cgmath(m: cgmath::Matrix4<f32>) -> [f32; 16] { core::array::from_fn(|i| m[i / 4][i % 4]) }
+
+// a 2d transform with rotation and translation so a transposed mat3 can't pass
+#[cfg(all(test, any(feature = "glam", feature = "mint", feature = "nalgebra", feature = "cgmath")))]
+const TEST_MAT3: [f32; 9] = [0.0, -1.0, 5.0, 1.0, 0.0, -2.0, 0.0, 0.0, 1.0];
+#[cfg(all(test, any(feature = "glam", feature = "mint", feature = "nalgebra", feature = "cgmath")))]
+fn test_mat4() -> [f32; 16] { core::array::from_fn(|i| if i % 5 == 0 { 1.0 } else { i as f32 * 0.25 }) }
+
+#[cfg(all(test, feature = "glam"))]
+#[test]
+fn test_glam() {
+    let v = vec3(1.0, -2.0, 3.0);
+    assert_eq!(Vec3::from(glam::Vec3::from(v)), v);
+    assert_eq!(Vec3::from(glam::Vec3A::from(v)), v);
+    assert_eq!(Vec2::from(glam::Vec2::from(v.xy())), v.xy());
+    let q = vec4(0.0, 0.6, 0.0, 0.8);
+    assert_eq!(glam::Quat::from(q), glam::Quat::from_rotation_y(2.0 * 0.6f32.atan2(0.8)));
+    assert_eq!(Vec4::from(glam::Quat::from(q)), q);
+
+    let g = glam::Mat3::from_translation(glam::vec2(5.0, -2.0)) * glam::Mat3::from_angle(core::f32::consts::FRAC_PI_2);
+    let m = mat3_from_glam(g);
+    assert!((mat3_trans_homog(vec2(3.0, 4.0), &m) - Vec2::from(g.transform_point2(glam::vec2(3.0, 4.0)))).norm() < 1e-5);
+    assert_eq!(mat3_from_glam(mat3_to_glam(&TEST_MAT3)), TEST_MAT3);
+    assert_eq!(mat3_to_glam(&TEST_MAT3).transform_point2(glam::vec2(3.0, 4.0)), mat3_trans_homog(vec2(3.0, 4.0), &TEST_MAT3).into());
+
+    let g = glam::Mat4::from_translation(glam::vec3(1.0, 2.0, 3.0)) * glam::Mat4::from_rotation_z(0.5);
+    let m = mat4_from_glam(g);
+    assert!((mat4_trans_homog(v, &m) - Vec3::from(g.transform_point3(v.into()))).norm() < 1e-5);
+    assert_eq!(mat4_from_glam(mat4_to_glam(&test_mat4())), test_mat4());
+}
+
+#[cfg(all(test, feature = "mint"))]
+#[test]
+fn test_mint() {
+    let v = vec4(1.0, -2.0, 3.0, 4.0);
+    assert_eq!(Vec4::from(mint::Vector4::from(v)), v);
+    assert_eq!(Vec3::from(mint::Point3::from(v.xyz())), v.xyz());
+    assert_eq!(Vec2::from(mint::Vector2::from(v.xy())), v.xy());
+    assert_eq!(mint::Quaternion::from(v).s, 4.0);
+    assert_eq!(Vec4::from(mint::Quaternion::from(v)), v);
+
+    let m = mat3_to_mint(&TEST_MAT3);
+    // the translation is the last column
+    assert_eq!((m.z.x, m.z.y), (5.0, -2.0));
+    assert_eq!(mat3_from_mint(m), TEST_MAT3);
+    let m = mat4_to_mint(&test_mat4());
+    assert_eq!((m.w.x, m.w.y, m.w.z), (3.0, 3.25, 3.5));
+    assert_eq!(mat4_from_mint(m), test_mat4());
+}
+
+#[cfg(all(test, feature = "nalgebra"))]
+#[test]
+fn test_nalgebra() {
+    let v = vec3(1.0, -2.0, 3.0);
+    assert_eq!(Vec3::from(nalgebra::Vector3::from(v)), v);
+    assert_eq!(Vec3::from(nalgebra::Point3::from(v)), v);
+    assert_eq!(Vec2::from(nalgebra::Point2::from(v.xy())), v.xy());
+    let q = vec4(0.0, 0.6, 0.0, 0.8);
+    assert_eq!(nalgebra::Quaternion::from(q).w, 0.8);
+    assert_eq!(Vec4::from(nalgebra::Quaternion::from(q)), q);
+
+    let n = nalgebra::Matrix3::new_translation(&nalgebra::Vector2::new(5.0, -2.0)) * nalgebra::Matrix3::new_rotation(0.3);
+    let m = mat3_from_nalgebra(n);
+    let p = n.transform_point(&nalgebra::Point2::new(3.0, 4.0));
+    assert!((mat3_trans_homog(vec2(3.0, 4.0), &m) - Vec2::from(p)).norm() < 1e-5);
+    assert_eq!(mat3_from_nalgebra(mat3_to_nalgebra(&TEST_MAT3)), TEST_MAT3);
+
+    let n = nalgebra::Matrix4::new_translation(&nalgebra::Vector3::new(1.0, 2.0, 3.0)) * nalgebra::Matrix4::from_euler_angles(0.1, 0.2, 0.3);
+    let m = mat4_from_nalgebra(n);
+    assert!((mat4_trans_homog(v, &m) - Vec3::from(n.transform_point(&v.into()))).norm() < 1e-5);
+    assert_eq!(mat4_from_nalgebra(mat4_to_nalgebra(&test_mat4())), test_mat4());
+}
+
+#[cfg(all(test, feature = "cgmath"))]
+#[test]
+fn test_cgmath() {
+    use cgmath::{Transform, Rotation3};
+    let v = vec3(1.0, -2.0, 3.0);
+    assert_eq!(Vec3::from(cgmath::Vector3::from(v)), v);
+    assert_eq!(Vec3::from(cgmath::Point3::from(v)), v);
+    assert_eq!(Vec2::from(cgmath::Vector2::from(v.xy())), v.xy());
+    let q = vec4(0.0, 0.6, 0.0, 0.8);
+    let r = cgmath::Quaternion::from_angle_y(cgmath::Rad(2.0 * 0.6f32.atan2(0.8)));
+    assert!((Vec4::from(r) - q).norm() < 1e-6);
+    assert_eq!(Vec4::from(cgmath::Quaternion::from(q)), q);
+
+    let c = cgmath::Matrix3::from_translation(cgmath::vec2(5.0, -2.0)) * cgmath::Matrix3::from_angle_z(cgmath::Rad(0.3));
+    let m = mat3_from_cgmath(c);
+    let p = c.transform_point(cgmath::Point2::new(3.0, 4.0));
+    assert!((mat3_trans_homog(vec2(3.0, 4.0), &m) - Vec2::from(p)).norm() < 1e-5);
+    assert_eq!(mat3_from_cgmath(mat3_to_cgmath(&TEST_MAT3)), TEST_MAT3);
+
+    let c = cgmath::Matrix4::from_translation(cgmath::vec3(1.0, 2.0, 3.0)) * cgmath::Matrix4::from_angle_x(cgmath::Rad(0.5));
+    let m = mat4_from_cgmath(c);
+    assert!((mat4_trans_homog(v, &m) - Vec3::from(c.transform_point(v.into()))).norm() < 1e-5);
+    assert_eq!(mat4_from_cgmath(mat4_to_cgmath(&test_mat4())), test_mat4());
+}
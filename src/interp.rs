@@ -0,0 +1,165 @@
+use crate::*;
+use core::f32::consts::PI;
+use core::ops::{Add, Sub, Mul};
+
+/// a at t = 0, b at t = 1
+pub fn lerp(a: f32, b: f32, t: f32) -> f32 { a + (b - a) * t }
+/// t such that lerp(a, b, t) == v, 0 for an empty range a == b
+pub fn inverse_lerp(a: f32, b: f32, v: f32) -> f32 { if a == b { 0.0 } else { (v - a) / (b - a) } }
+/// map v from the range a0..a1 to b0..b1, an empty a0..a1 maps everything to b0
+pub fn remap(v: f32, a0: f32, a1: f32, b0: f32, b1: f32) -> f32 { lerp(b0, b1, inverse_lerp(a0, a1, v)) }
+
+/// interpolate the corners of a unit square, c indexed by x + 2y. works on f32 and all the vectors
+pub fn bilinear<T: Copy + Add<Output = T> + Mul<f32, Output = T>>(c: [T; 4], uv: Vec2) -> T {
+    let (u, v) = (uv.x, uv.y);
+    c[0] * ((1.0 - u) * (1.0 - v)) + c[1] * (u * (1.0 - v)) + c[2] * ((1.0 - u) * v) + c[3] * (u * v)
+}
+
+/// interpolate the corners of a unit cube, c indexed by x + 2y + 4z
+pub fn trilinear<T: Copy + Add<Output = T> + Mul<f32, Output = T>>(c: [T; 8], uvw: Vec3) -> T {
+    let lo = bilinear([c[0], c[1], c[2], c[3]], vec2(uvw.x, uvw.y));
+    let hi = bilinear([c[4], c[5], c[6], c[7]], vec2(uvw.x, uvw.y));
+    lo * (1.0 - uvw.z) + hi * uvw.z
+}
+
+/// the usual easing curves (see easings.net), all going from 0 at t = 0 to 1 at t = 1.
+/// elastic and back overshoot in between
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Easing {
+    Linear,
+    InQuad, OutQuad, InOutQuad,
+    InCubic, OutCubic, InOutCubic,
+    InExpo, OutExpo, InOutExpo,
+    InElastic, OutElastic, InOutElastic,
+    InBack, OutBack, InOutBack,
+    InBounce, OutBounce, InOutBounce,
+}
+
+fn out_bounce(t: f32) -> f32 {
+    let n = 7.5625;
+    let d = 2.75;
+    if t < 1.0 / d {
+        n * t * t
+    } else if t < 2.0 / d {
+        let t = t - 1.5 / d;
+        n * t * t + 0.75
+    } else if t < 2.5 / d {
+        let t = t - 2.25 / d;
+        n * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / d;
+        n * t * t + 0.984375
+    }
+}
+
+impl Easing {
+    /// eased t, t is clamped to [0, 1] first
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        let back = 1.70158;
+        let back_io = back * 1.525;
+        let elastic = 2.0 * PI / 3.0;
+        let elastic_io = 2.0 * PI / 4.5;
+        match self {
+            Easing::Linear => t,
+            Easing::InQuad => t * t,
+            Easing::OutQuad => 1.0 - (1.0 - t).powi(2),
+            Easing::InOutQuad => if t < 0.5 { 2.0 * t * t } else { 1.0 - (2.0 - 2.0 * t).powi(2) / 2.0 },
+            Easing::InCubic => t * t * t,
+            Easing::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::InOutCubic => if t < 0.5 { 4.0 * t * t * t } else { 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0 },
+            Easing::InExpo => if t == 0.0 { 0.0 } else { 2.0f32.powf(10.0 * t - 10.0) },
+            Easing::OutExpo => if t == 1.0 { 1.0 } else { 1.0 - 2.0f32.powf(-10.0 * t) },
+            Easing::InOutExpo => {
+                if t == 0.0 || t == 1.0 { t }
+                else if t < 0.5 { 2.0f32.powf(20.0 * t - 10.0) / 2.0 }
+                else { (2.0 - 2.0f32.powf(10.0 - 20.0 * t)) / 2.0 }
+            }
+            Easing::InElastic => {
+                if t == 0.0 || t == 1.0 { t }
+                else { -(2.0f32.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * elastic).sin() }
+            }
+            Easing::OutElastic => {
+                if t == 0.0 || t == 1.0 { t }
+                else { 2.0f32.powf(-10.0 * t) * ((10.0 * t - 0.75) * elastic).sin() + 1.0 }
+            }
+            Easing::InOutElastic => {
+                if t == 0.0 || t == 1.0 { t }
+                else if t < 0.5 { -(2.0f32.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * elastic_io).sin()) / 2.0 }
+                else { 2.0f32.powf(10.0 - 20.0 * t) * ((20.0 * t - 11.125) * elastic_io).sin() / 2.0 + 1.0 }
+            }
+            Easing::InBack => (back + 1.0) * t * t * t - back * t * t,
+            Easing::OutBack => 1.0 + (back + 1.0) * (t - 1.0).powi(3) + back * (t - 1.0).powi(2),
+            Easing::InOutBack => {
+                if t < 0.5 { (2.0 * t).powi(2) * ((back_io + 1.0) * 2.0 * t - back_io) / 2.0 }
+                else { ((2.0 * t - 2.0).powi(2) * ((back_io + 1.0) * (2.0 * t - 2.0) + back_io) + 2.0) / 2.0 }
+            }
+            Easing::InBounce => 1.0 - out_bounce(1.0 - t),
+            Easing::OutBounce => out_bounce(t),
+            Easing::InOutBounce => {
+                if t < 0.5 { (1.0 - out_bounce(1.0 - 2.0 * t)) / 2.0 }
+                else { (1.0 + out_bounce(2.0 * t - 1.0)) / 2.0 }
+            }
+        }
+    }
+}
+
+/// a at t = 0 to b at t = 1 along an easing curve. works on f32 and all the vectors
+pub fn tween<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>>(a: T, b: T, t: f32, easing: Easing) -> T {
+    a + (b - a) * easing.apply(t)
+}
+
+#[test]
+fn test_lerp_remap() {
+    assert_eq!(lerp(2.0, 4.0, 0.0), 2.0);
+    assert_eq!(lerp(2.0, 4.0, 0.25), 2.5);
+    assert_eq!(inverse_lerp(2.0, 4.0, 2.5), 0.25);
+    assert_eq!(remap(5.0, 0.0, 10.0, -1.0, 1.0), 0.0);
+    assert_eq!(remap(15.0, 0.0, 10.0, -1.0, 1.0), 2.0);
+    assert_eq!(inverse_lerp(3.0, 3.0, 7.0), 0.0);
+    assert_eq!(remap(7.0, 3.0, 3.0, -1.0, 1.0), -1.0);
+    assert_eq!(vec2(4.0, 7.0).inverse_lerp(vec2(2.0, 3.0), vec2(6.0, 3.0)), vec2(0.5, 0.0));
+    assert_eq!(vec2(4.0, 7.0).remap(vec2(2.0, 3.0), vec2(6.0, 3.0), Vec2::splat(10.0), Vec2::splat(20.0)), vec2(15.0, 10.0));
+    assert_eq!(vec2(1.0, 2.0).lerp_to(vec2(3.0, 6.0), 0.25), vec2(1.5, 3.0));
+    assert_eq!(vec2(5.0, 0.0).remap(Vec2::splat(0.0), Vec2::splat(10.0), vec2(0.0, 1.0), vec2(1.0, 2.0)), vec2(0.5, 1.0));
+}
+
+#[test]
+fn test_bilinear_trilinear() {
+    let c = [0.0, 1.0, 2.0, 4.0];
+    assert_eq!(bilinear(c, vec2(0.0, 0.0)), 0.0);
+    assert_eq!(bilinear(c, vec2(1.0, 1.0)), 4.0);
+    assert_eq!(bilinear(c, vec2(0.5, 0.5)), 1.75);
+    assert_eq!(bilinear(c.map(|x| vec2(x, -x)), vec2(0.5, 0.5)), vec2(1.75, -1.75));
+    // trilinear of a linear function is exact
+    let f = |p: Vec3| p.x + 2.0 * p.y - 3.0 * p.z;
+    let corners: [f32; 8] = core::array::from_fn(|i| f(vec3((i & 1) as f32, ((i >> 1) & 1) as f32, (i >> 2) as f32)));
+    let p = vec3(0.25, 0.5, 0.75);
+    assert!((trilinear(corners, p) - f(p)).abs() < 1e-6);
+}
+
+#[test]
+fn test_easing() {
+    use Easing::*;
+    let all = [
+        Linear, InQuad, OutQuad, InOutQuad, InCubic, OutCubic, InOutCubic, InExpo, OutExpo, InOutExpo,
+        InElastic, OutElastic, InOutElastic, InBack, OutBack, InOutBack, InBounce, OutBounce, InOutBounce,
+    ];
+    for e in all {
+        assert!(e.apply(0.0).abs() < 1e-3, "{:?}", e);
+        assert!((e.apply(1.0) - 1.0).abs() < 1e-3, "{:?}", e);
+        // continuous: no big jumps anywhere
+        for i in 0..100 {
+            let (a, b) = (e.apply(i as f32 / 100.0), e.apply((i + 1) as f32 / 100.0));
+            assert!((a - b).abs() < 0.2, "{:?} at {}", e, i);
+        }
+    }
+    assert_eq!(InQuad.apply(0.5), 0.25);
+    assert_eq!(OutQuad.apply(0.5), 0.75);
+    assert_eq!(InOutCubic.apply(0.5), 0.5);
+    assert!(InBack.apply(0.2) < 0.0);
+    assert!(OutBack.apply(0.8) > 1.0);
+    assert_eq!(OutBounce.apply(1.0 / 2.75), 1.0);
+    assert_eq!(tween(vec3(0.0, 0.0, 0.0), vec3(4.0, 8.0, 0.0), 0.5, InQuad), vec3(1.0, 2.0, 0.0));
+    assert_eq!(tween(10.0, 20.0, 2.0, Linear), 20.0);
+}
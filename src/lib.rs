@@ -1,13 +1,106 @@
+//! a mini vector library. builds without std given the `libm` feature, which then
+//! supplies the float math std would otherwise provide. with both std and libm on,
+//! std's float methods win and libm goes unused
+#![no_std]
+// with neither std nor libm std is linked anyway, so the compile_error below is the
+// only error rather than the first of one per float method call
+#[cfg(any(feature = "std", not(feature = "libm"), test))]
+extern crate std;
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("minvect needs the `std` or `libm` feature for float math, build with `--no-default-features --features libm` for no_std");
+
+#[allow(unused_imports)]
+#[cfg(all(feature = "libm", not(any(feature = "std", test))))]
+use math::FloatMath;
+#[allow(unused_imports)]
+use alloc::{boxed::Box, format, string::{String, ToString}, vec, vec::Vec};
+
+#[macro_use]
+mod macros;
 mod vec2;
 mod vec3;
 mod vec4;
+mod vector;
+mod swizzle;
 mod rect;
 mod mat3;
 mod mat4;
+mod path;
+mod svg;
+mod polyline;
+mod sdf2;
+mod sdf3;
+mod marching;
+mod interp;
+mod complex;
+mod fft;
+mod color;
+mod css;
+mod blend;
+mod gradient;
+mod colorsci;
+mod gpu;
+mod pack;
+mod text;
+mod convert;
+#[cfg(any(feature = "glam", feature = "mint", feature = "nalgebra", feature = "cgmath"))]
+mod interop;
+mod math;
+#[cfg(feature = "serde")]
+pub mod compact;
 
 pub use vec2::*;
 pub use vec3::*;
 pub use vec4::*;
+pub use vector::*;
 pub use rect::*;
 pub use mat3::*;
-pub use mat4::*;
\ No newline at end of file
+pub use mat4::*;
+pub use path::*;
+pub use svg::*;
+pub use polyline::*;
+pub use sdf2::*;
+pub use sdf3::*;
+pub use marching::*;
+pub use interp::*;
+pub use complex::*;
+pub use fft::*;
+pub use color::*;
+pub use css::*;
+pub use blend::*;
+pub use gradient::*;
+pub use colorsci::*;
+pub use gpu::*;
+pub use pack::*;
+pub use text::*;
+#[cfg(any(feature = "glam", feature = "mint", feature = "nalgebra", feature = "cgmath"))]
+pub use interop::*;
+
+/// the no_std configurations still compile and the libm math agrees with std, all built
+/// into their own target dir so as not to wait on the lock held by this build
+#[test]
+fn test_no_std_build() {
+    let dir = env!("CARGO_MANIFEST_DIR");
+    let cargo = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO"))
+            .args(args)
+            .arg("--manifest-path").arg(format!("{dir}/Cargo.toml"))
+            .arg("--target-dir").arg(format!("{dir}/target/no_std_check"))
+            .output()
+            .unwrap()
+    };
+    for features in ["libm", "libm,serde,bytemuck,glam,mint,nalgebra"] {
+        let out = cargo(&["check", "--lib", "--quiet", "--no-default-features", "--features", features]);
+        assert!(out.status.success(), "no_std build failed with features {features}: {}", String::from_utf8_lossy(&out.stderr));
+    }
+    let out = cargo(&["test", "--lib", "--quiet", "--no-default-features", "--features", "libm", "math::"]);
+    assert!(out.status.success(), "libm math tests failed: {}", String::from_utf8_lossy(&out.stdout));
+    // no float math at all is one clear error, not one per call site
+    let out = cargo(&["check", "--lib", "--quiet", "--no-default-features"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!out.status.success());
+    assert!(stderr.contains("needs the `std` or `libm` feature"), "{stderr}");
+    assert_eq!(stderr.matches("error:").count(), 2, "{stderr}");
+}
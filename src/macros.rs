@@ -0,0 +1,330 @@
+// the shared core of Vec2, Vec3 and Vec4, so the three files cant drift apart.
+// each type keeps its struct definition and anything dimension specific (cross, complex numbers, colours) in its own file
+
+/// everything component wise: map/zip_map/fold, the common methods, every operator against Self and f32
+/// (both sides, by value and by reference), the assigning operators, Display and FromStr
+macro_rules! impl_vector {
+    ($t:ident { $($f:ident),+ }) => {
+        impl $t {
+            /// apply f to each component
+            pub fn map(&self, f: impl Fn(f32) -> f32) -> Self { $t { $($f: f(self.$f)),+ } }
+            /// apply f to each pair of components
+            pub fn zip_map(&self, other: Self, f: impl Fn(f32, f32) -> f32) -> Self { $t { $($f: f(self.$f, other.$f)),+ } }
+            /// combine the components in order x, y, z, w
+            pub fn fold<T>(&self, init: T, mut f: impl FnMut(T, f32) -> T) -> T {
+                let acc = init;
+                $(let acc = f(acc, self.$f);)+
+                acc
+            }
+            pub fn norm(&self) -> f32 { self.dot(*self).sqrt() }
+            pub fn dist(&self, other: Self) -> f32 { (*self - other).norm() }
+            /// other at t = 0 and self at t = 1, the reverse of glsl mix and of lerp_to. a.lerp(b, t) == b.lerp_to(a, t)
+            #[deprecated(since = "0.2.0", note = "lerp gives other at t = 0 and self at t = 1, use lerp_to which goes from self to other: a.lerp(b, t) == b.lerp_to(a, t)")]
+            pub fn lerp(&self, other: Self, t: f32) -> Self { *self * t + other * (1.0 - t) }
+            /// self at t = 0, other at t = 1, same as glsl mix
+            pub fn lerp_to(&self, other: Self, t: f32) -> Self { *self + (other - *self) * t }
+            /// lerp_to then normalize, cheap approximation of slerp
+            pub fn nlerp(&self, other: Self, t: f32) -> Self { self.lerp_to(other, t).normalize_or_zero() }
+            /// constant angular speed between unit vectors self and other
+            pub fn slerp(&self, other: Self, t: f32) -> Self {
+                let d = self.dot(other).clamp(-1.0, 1.0);
+                if d > 0.9995 {
+                    return self.nlerp(other, t);
+                }
+                // opposite directions: any great circle will do, go via some perpendicular
+                let (theta, perp) = if d < -0.9995 {
+                    (core::f32::consts::PI, crate::Vector::orthogonal(self).normalize_or_zero())
+                } else {
+                    (d.acos(), (other - *self * d).normalize_or_zero())
+                };
+                let (s, c) = (theta * t).sin_cos();
+                *self * c + perp * s
+            }
+            /// per component t such that a.lerp_to(b, t) == self, 0 where a and b are equal
+            pub fn inverse_lerp(&self, a: Self, b: Self) -> Self { $t { $($f: crate::inverse_lerp(a.$f, b.$f, self.$f)),+ } }
+            /// map self from the range a0..a1 to b0..b1 per component, b0 where a0..a1 is empty
+            pub fn remap(&self, a0: Self, a1: Self, b0: Self, b1: Self) -> Self { b0.mix(b1, self.inverse_lerp(a0, a1)) }
+            pub fn unit(&self) -> Option<Self> { let n = self.norm(); if n == 0.0 {None} else {Some(*self/n)}}
+            pub fn dot(&self, other: Self) -> f32 { (*self * other).fold(0.0, |acc, c| acc + c) }
+            pub fn min(&self, other: Self) -> Self { self.zip_map(other, f32::min) }
+            pub fn max(&self, other: Self) -> Self { self.zip_map(other, f32::max) }
+            pub fn floor(&self) -> Self { self.map(f32::floor) }
+            pub fn ceil(&self) -> Self { self.map(f32::ceil) }
+
+            // glsl style component wise functions
+            /// all components v
+            pub fn splat(v: f32) -> Self { $t { $($f: v),+ } }
+            pub fn abs(&self) -> Self { self.map(f32::abs) }
+            /// -1, 0 or 1 like glsl, not f32::signum which gives 1 for 0
+            pub fn sign(&self) -> Self { self.map(|c| if c > 0.0 { 1.0 } else if c < 0.0 { -1.0 } else { 0.0 }) }
+            /// x - floor(x), always in [0, 1)
+            pub fn fract(&self) -> Self { *self - self.floor() }
+            pub fn round(&self) -> Self { self.map(f32::round) }
+            pub fn trunc(&self) -> Self { self.map(f32::trunc) }
+            pub fn clamp(&self, lo: Self, hi: Self) -> Self { self.max(lo).min(hi) }
+            /// clamp to [0, 1]
+            pub fn saturate(&self) -> Self { self.clamp(Self::splat(0.0), Self::splat(1.0)) }
+            /// glsl mix: self at t = 0 and other at t = 1, per component
+            pub fn mix(&self, other: Self, t: Self) -> Self { *self + (other - *self) * t }
+            /// 0 where self < edge, 1 otherwise
+            pub fn step(&self, edge: Self) -> Self { self.zip_map(edge, |c, e| if c < e { 0.0 } else { 1.0 }) }
+            /// hermite from 0 at e0 to 1 at e1
+            pub fn smoothstep(&self, e0: Self, e1: Self) -> Self {
+                let t = ((*self - e0) / (e1 - e0)).saturate();
+                t * t * (3.0 - 2.0 * t)
+            }
+            /// remainder that is never negative, unlike %
+            pub fn rem_euclid(&self, rhs: Self) -> Self { self.zip_map(rhs, f32::rem_euclid) }
+            pub fn pow(&self, e: Self) -> Self { self.zip_map(e, f32::powf) }
+            pub fn powf(&self, e: f32) -> Self { self.map(|c| c.powf(e)) }
+            pub fn exp(&self) -> Self { self.map(f32::exp) }
+            /// natural log
+            pub fn ln(&self) -> Self { self.map(f32::ln) }
+            pub fn sqrt(&self) -> Self { self.map(f32::sqrt) }
+            pub fn recip(&self) -> Self { self.map(f32::recip) }
+            pub fn sin(&self) -> Self { self.map(f32::sin) }
+            pub fn cos(&self) -> Self { self.map(f32::cos) }
+            pub fn min_element(&self) -> f32 { self.fold(f32::INFINITY, f32::min) }
+            pub fn max_element(&self) -> f32 { self.fold(f32::NEG_INFINITY, f32::max) }
+            pub fn sum(&self) -> f32 { self.fold(0.0, |acc, c| acc + c) }
+            pub fn product(&self) -> f32 { self.fold(1.0, |acc, c| acc * c) }
+            /// true if every component is finite
+            pub fn is_finite(&self) -> bool { self.fold(true, |acc, c| acc && c.is_finite()) }
+            /// true if any component is nan
+            pub fn is_nan(&self) -> bool { self.fold(false, |acc, c| acc || c.is_nan()) }
+
+            /// like unit but also None if the length overflowed or went nan
+            pub fn try_normalize(&self) -> Option<Self> {
+                let n = self.norm();
+                if n == 0.0 || !n.is_finite() { None } else { Some(*self / n) }
+            }
+            pub fn normalize_or_zero(&self) -> Self { self.try_normalize().unwrap_or_default() }
+            /// component of self along other
+            pub fn project(&self, other: Self) -> Self {
+                let l2 = other.dot(other);
+                if l2 == 0.0 { Self::default() } else { other * (self.dot(other) / l2) }
+            }
+            /// component of self perpendicular to other
+            pub fn reject(&self, other: Self) -> Self { *self - self.project(other) }
+            /// mirror self (eg an incoming direction) about the plane with unit normal n
+            pub fn reflect(&self, n: Self) -> Self { *self - n * (2.0 * n.dot(*self)) }
+            /// glsl refract: self and n unit, eta the ratio of refractive indices. None on total internal reflection
+            pub fn refract(&self, n: Self, eta: f32) -> Option<Self> {
+                let d = n.dot(*self);
+                let k = 1.0 - eta * eta * (1.0 - d * d);
+                if k < 0.0 { None } else { Some(*self * eta - n * (eta * d + k.sqrt())) }
+            }
+            /// unsigned angle between self and other in radians, 0 if either is zero
+            pub fn angle_between(&self, other: Self) -> f32 {
+                let d = self.norm() * other.norm();
+                if d == 0.0 { 0.0 } else { (self.dot(other) / d).clamp(-1.0, 1.0).acos() }
+            }
+        }
+        impl core::ops::Neg for $t {
+            type Output = $t;
+            fn neg(self) -> $t { self.map(|c| -c) }
+        }
+        impl core::ops::Neg for &$t {
+            type Output = $t;
+            fn neg(self) -> $t { -*self }
+        }
+        impl_vector!(@binop $t, Add, add, AddAssign, add_assign, +);
+        impl_vector!(@binop $t, Sub, sub, SubAssign, sub_assign, -);
+        impl_vector!(@binop $t, Mul, mul, MulAssign, mul_assign, *);
+        impl_vector!(@binop $t, Div, div, DivAssign, div_assign, /);
+        impl_vector!(@binop $t, Rem, rem, RemAssign, rem_assign, %);
+        /// "(x, y, ...)", width, precision and sign if given apply to every component
+        impl core::fmt::Display for $t {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result { crate::fmt_components(f, &[$(self.$f),+]) }
+        }
+        /// n numbers separated by commas and/or whitespace, optionally in () or [], "1,2", "(1, 2)", "[1 2]"
+        impl core::str::FromStr for $t {
+            type Err = crate::ParseVecError;
+            fn from_str(s: &str) -> Result<$t, crate::ParseVecError> { crate::parse_components(s).map(|[$($f),+]| $t { $($f),+ }) }
+        }
+    };
+    (@binop $t:ident, $op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, $sym:tt) => {
+        impl core::ops::$op<$t> for $t {
+            type Output = $t;
+            fn $fn(self, rhs: $t) -> $t { self.zip_map(rhs, |a, b| a $sym b) }
+        }
+        impl core::ops::$op<f32> for $t {
+            type Output = $t;
+            fn $fn(self, rhs: f32) -> $t { self.map(|a| a $sym rhs) }
+        }
+        impl core::ops::$op<$t> for f32 {
+            type Output = $t;
+            fn $fn(self, rhs: $t) -> $t { rhs.map(|b| self $sym b) }
+        }
+        impl core::ops::$op<&$t> for $t {
+            type Output = $t;
+            fn $fn(self, rhs: &$t) -> $t { self $sym *rhs }
+        }
+        impl core::ops::$op<$t> for &$t {
+            type Output = $t;
+            fn $fn(self, rhs: $t) -> $t { *self $sym rhs }
+        }
+        impl core::ops::$op<&$t> for &$t {
+            type Output = $t;
+            fn $fn(self, rhs: &$t) -> $t { *self $sym *rhs }
+        }
+        impl core::ops::$op<f32> for &$t {
+            type Output = $t;
+            fn $fn(self, rhs: f32) -> $t { *self $sym rhs }
+        }
+        impl core::ops::$op<&$t> for f32 {
+            type Output = $t;
+            fn $fn(self, rhs: &$t) -> $t { self $sym *rhs }
+        }
+        impl core::ops::$op_assign<$t> for $t {
+            fn $fn_assign(&mut self, rhs: $t) { *self = *self $sym rhs; }
+        }
+        impl core::ops::$op_assign<&$t> for $t {
+            fn $fn_assign(&mut self, rhs: &$t) { *self = *self $sym *rhs; }
+        }
+        impl core::ops::$op_assign<f32> for $t {
+            fn $fn_assign(&mut self, rhs: f32) { *self = *self $sym rhs; }
+        }
+    };
+}
+
+/// checks every operator of a vector type against doing it by hand per component
+#[cfg(test)]
+macro_rules! test_vector_ops {
+    ($name:ident, $t:ident { $($f:ident),+ }) => {
+        #[test]
+        fn $name() {
+            use crate::*;
+            let mut i = 0.0;
+            let a = $t { $($f: { i += 1.0; i * 1.5 - 2.0 }),+ };
+            let b = $t { $($f: { i += 1.0; i * 0.5 + 0.25 }),+ };
+            let s = 1.75;
+            macro_rules! check {
+                ($sym:tt, $sym_assign:tt) => {
+                    let vv = $t { $($f: a.$f $sym b.$f),+ };
+                    let vs = $t { $($f: a.$f $sym s),+ };
+                    let sv = $t { $($f: s $sym b.$f),+ };
+                    assert_eq!(a $sym b, vv);
+                    assert_eq!(&a $sym b, vv);
+                    assert_eq!(a $sym &b, vv);
+                    assert_eq!(&a $sym &b, vv);
+                    assert_eq!(a $sym s, vs);
+                    assert_eq!(&a $sym s, vs);
+                    assert_eq!(s $sym b, sv);
+                    assert_eq!(s $sym &b, sv);
+                    let mut c = a; c $sym_assign b; assert_eq!(c, vv);
+                    let mut c = a; c $sym_assign &b; assert_eq!(c, vv);
+                    let mut c = a; c $sym_assign s; assert_eq!(c, vs);
+                };
+            }
+            check!(+, +=);
+            check!(-, -=);
+            check!(*, *=);
+            check!(/, /=);
+            check!(%, %=);
+            assert_eq!(-a, $t { $($f: -a.$f),+ });
+            assert_eq!(-&a, $t { $($f: -a.$f),+ });
+            assert_eq!(a.map(|c| c * 2.0), $t { $($f: a.$f * 2.0),+ });
+            assert_eq!(a.zip_map(b, f32::max), $t { $($f: a.$f.max(b.$f)),+ });
+            assert_eq!(a.fold(0.0, |acc, c| acc + c), 0.0 $(+ a.$f)+);
+            assert_eq!(a.dot(b), 0.0 $(+ a.$f * b.$f)+);
+            assert_eq!(a.min(b), $t { $($f: a.$f.min(b.$f)),+ });
+            assert_eq!(a.max(b), $t { $($f: a.$f.max(b.$f)),+ });
+            assert_eq!(a.floor(), $t { $($f: a.$f.floor()),+ });
+            assert_eq!(a.ceil(), $t { $($f: a.$f.ceil()),+ });
+            assert!((a.unit().unwrap().norm() - 1.0).abs() < 1e-6);
+            assert_eq!($t::default().unit(), None);
+
+            // component wise maths against the f32 function
+            macro_rules! check_map {
+                ($m:ident) => { assert_eq!(a.$m(), $t { $($f: a.$f.$m()),+ }); };
+                ($m:ident, $g:expr) => { assert_eq!(a.$m(), $t { $($f: $g(a.$f)),+ }); };
+            }
+            check_map!(abs);
+            check_map!(round);
+            check_map!(trunc);
+            check_map!(exp);
+            check_map!(sin);
+            check_map!(cos);
+            check_map!(recip);
+            check_map!(sign, |c: f32| if c == 0.0 { 0.0 } else { c.signum() });
+            check_map!(fract, |c: f32| c - c.floor());
+            check_map!(saturate, |c: f32| c.clamp(0.0, 1.0));
+            assert_eq!(b.sqrt(), $t { $($f: b.$f.sqrt()),+ });
+            assert_eq!(b.ln(), $t { $($f: b.$f.ln()),+ });
+            assert_eq!(a.abs().powf(s), $t { $($f: a.$f.abs().powf(s)),+ });
+            assert_eq!(b.pow(a), $t { $($f: b.$f.powf(a.$f)),+ });
+            assert_eq!(a.rem_euclid(b), $t { $($f: a.$f.rem_euclid(b.$f)),+ });
+            assert_eq!(a.clamp(b * 0.5, b), $t { $($f: a.$f.clamp(b.$f * 0.5, b.$f)),+ });
+            assert_eq!(a.mix(b, $t::splat(0.25)), a + (b - a) * 0.25);
+            assert_eq!(a.step(b), $t { $($f: if a.$f < b.$f { 0.0 } else { 1.0 }),+ });
+            assert_eq!(a.sum(), 0.0 $(+ a.$f)+);
+            assert_eq!(a.product(), 1.0 $(* a.$f)+);
+            assert_eq!(a.min_element(), f32::INFINITY $(.min(a.$f))+);
+            assert_eq!(a.max_element(), f32::NEG_INFINITY $(.max(a.$f))+);
+            assert!((a.project(b) + a.reject(b) - a).norm() < 1e-5);
+            assert!(a.reject(b).dot(b).abs() < 1e-4);
+            assert_eq!(a.project($t::default()), $t::default());
+            assert!((a.try_normalize().unwrap() - a.unit().unwrap()).norm() < 1e-6);
+            assert_eq!($t::splat(f32::MAX).try_normalize(), None);
+            assert_eq!($t::default().normalize_or_zero(), $t::default());
+            let n = b.unit().unwrap();
+            let r = a.reflect(n);
+            assert!((r.norm() - a.norm()).abs() < 1e-4);
+            assert!((r.dot(n) + a.dot(n)).abs() < 1e-4);
+            assert!((a.angle_between(a * 3.0)).abs() < 1e-3);
+            assert!((a.angle_between(-a) - core::f32::consts::PI).abs() < 1e-3);
+            assert_eq!(a.lerp_to(b, 0.0), a);
+            assert_eq!(a.lerp_to(b, 1.0), b);
+            assert_eq!(a.lerp_to(b, 0.5), (a + b) / 2.0);
+            // the old lerp keeps its meaning for now
+            #[allow(deprecated)]
+            let old = (a.lerp(b, 0.0), a.lerp(b, 1.0), a.lerp(b, 0.25));
+            assert_eq!(old, (b, a, b.lerp_to(a, 0.25)));
+            assert!((a.lerp_to(b, 0.3).inverse_lerp(a, b) - $t::splat(0.3)).abs().max_element() < 1e-5);
+            assert!((a.remap(a, b, b, a) - b).abs().max_element() < 1e-5);
+            let (ua, ub) = (a.unit().unwrap(), b.unit().unwrap());
+            let theta = ua.angle_between(ub);
+            for i in 0..=4 {
+                let t = i as f32 / 4.0;
+                let v = ua.slerp(ub, t);
+                assert!((v.norm() - 1.0).abs() < 1e-5);
+                assert!((ua.angle_between(v) - theta * t).abs() < 1e-3);
+                assert!((ua.nlerp(ub, t).norm() - 1.0).abs() < 1e-5);
+            }
+            assert!((ua.slerp(-ua, 0.5).dot(ua)).abs() < 1e-5);
+            assert!((ua.slerp(-ua, 1.0) + ua).norm() < 1e-5);
+            assert!(a.is_finite() && !a.is_nan());
+            assert!(!(a / 0.0).is_finite());
+            assert!((a * f32::NAN).is_nan());
+        }
+    };
+}
+
+#[cfg(test)]
+test_vector_ops!(test_vec2_ops, Vec2 { x, y });
+#[cfg(test)]
+test_vector_ops!(test_vec3_ops, Vec3 { x, y, z });
+#[cfg(test)]
+test_vector_ops!(test_vec4_ops, Vec4 { x, y, z, w });
+
+#[test]
+fn test_vector_glsl_edge_cases() {
+    use crate::*;
+    assert_eq!(vec3(-2.0, 0.0, 3.0).sign(), vec3(-1.0, 0.0, 1.0));
+    assert_eq!(vec2(-0.25, 1.75).fract(), vec2(0.75, 0.75));
+    assert_eq!(vec2(-1.0, 5.0).rem_euclid(vec2(3.0, 3.0)), vec2(2.0, 2.0));
+    let e0 = Vec4::splat(0.0);
+    let e1 = Vec4::splat(2.0);
+    assert_eq!(vec4(-1.0, 0.5, 1.0, 3.0).smoothstep(e0, e1), vec4(0.0, 0.15625, 0.5, 1.0));
+    assert!(vec3(1.0, f32::NAN, 0.0).is_nan());
+    assert!(!vec3(1.0, f32::INFINITY, 0.0).is_finite());
+}
+
+#[test]
+fn test_vector_display() {
+    use crate::*;
+    assert_eq!(format!("{}", vec2(1.0, 2.5)), "(1, 2.5)");
+    assert_eq!(format!("{}", vec3(1.0, 2.5, -3.0)), "(1, 2.5, -3)");
+    assert_eq!(format!("{:.2}", vec4(1.0, 2.5, -3.0, 0.126)), "(1.00, 2.50, -3.00, 0.13)");
+}
@@ -0,0 +1,305 @@
+use crate::*;
+use alloc::collections::BTreeMap;
+
+// inside is where the field is below iso, so sdfs work directly.
+// samples are row major with x fastest, sample (i, j) sits at bounds.rect_to_world(vec2(i / (nx-1), j / (ny-1)))
+
+/// samples f on an nx by ny grid of points spanning bounds, for marching_squares
+pub fn sample_grid2(f: impl Fn(Vec2) -> f32, bounds: Rect, nx: usize, ny: usize) -> Vec<f32> {
+    let mut out = Vec::with_capacity(nx * ny);
+    for j in 0..ny {
+        for i in 0..nx {
+            out.push(f(grid_point2(bounds, nx, ny, i, j)));
+        }
+    }
+    out
+}
+
+fn grid_point2(bounds: Rect, nx: usize, ny: usize, i: usize, j: usize) -> Vec2 {
+    let d = vec2((nx.max(2) - 1) as f32, (ny.max(2) - 1) as f32);
+    bounds.rect_to_world(vec2(i as f32, j as f32) / d)
+}
+
+/// sign changes around one cell or face with corners in anticlockwise order, as (exit edge, entry edge) pairs.
+/// edge k runs from corner k to corner k + 1. segments go exit -> entry which keeps the inside on the left.
+/// saddles are split using the average of the corners
+fn cell_segments(inside: [bool; 4], values: [f32; 4], iso: f32) -> Vec<(usize, usize)> {
+    let exits: Vec<usize> = (0..4).filter(|&k| inside[k] && !inside[(k + 1) % 4]).collect();
+    let entries: Vec<usize> = (0..4).filter(|&k| !inside[k] && inside[(k + 1) % 4]).collect();
+    match exits.len() {
+        0 => vec![],
+        1 => vec![(exits[0], entries[0])],
+        _ => {
+            let joined = values.iter().sum::<f32>() / 4.0 < iso;
+            exits.iter().map(|&k| (k, if joined { (k + 1) % 4 } else { (k + 3) % 4 })).collect()
+        }
+    }
+}
+
+/// iso contours of a 2d scalar field as closed polylines, anticlockwise around the inside (with y up).
+/// the area outside the grid counts as outside so contours touching the edge get closed along it
+pub fn marching_squares(samples: &[f32], nx: usize, ny: usize, bounds: Rect, iso: f32) -> Vec<Vec<Vec2>> {
+    assert_eq!(samples.len(), nx * ny);
+    // pad by a ring of outside samples sitting on top of the boundary samples
+    let (w, h) = (nx + 2, ny + 2);
+    let unpad = |i: usize, n: usize| i.saturating_sub(1).min(n - 1);
+    let value = |i: usize, j: usize| {
+        if i == 0 || j == 0 || i == w - 1 || j == h - 1 { f32::INFINITY } else { samples[(j - 1) * nx + i - 1] }
+    };
+    let pos = |i: usize, j: usize| grid_point2(bounds, nx, ny, unpad(i, nx), unpad(j, ny));
+    // edge ids: 2 * point index, +1 for the vertical edge going up from that point
+    let edge_point = |e: usize| {
+        let (i, j) = ((e / 2) % w, (e / 2) / w);
+        let (i1, j1) = if e & 1 == 0 { (i + 1, j) } else { (i, j + 1) };
+        let (a, b) = (value(i, j), value(i1, j1));
+        let t = if a.is_infinite() { 1.0 } else if b.is_infinite() { 0.0 } else { (iso - a) / (b - a) };
+        let (pa, pb) = (pos(i, j), pos(i1, j1));
+        pa + (pb - pa) * t
+    };
+
+    let mut next: BTreeMap<usize, usize> = BTreeMap::new();
+    for j in 0..h - 1 {
+        for i in 0..w - 1 {
+            let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)];
+            let values = corners.map(|(i, j)| value(i, j));
+            let edges = [2 * (j * w + i), 2 * (j * w + i + 1) + 1, 2 * ((j + 1) * w + i), 2 * (j * w + i) + 1];
+            for (a, b) in cell_segments(values.map(|v| v < iso), values, iso) {
+                next.insert(edges[a], edges[b]);
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut starts: Vec<usize> = next.keys().copied().collect();
+    starts.sort();
+    for s in starts {
+        let Some(mut e) = next.remove(&s) else { continue };
+        let mut contour = vec![edge_point(s)];
+        while e != s {
+            contour.push(edge_point(e));
+            e = next.remove(&e).unwrap();
+        }
+        contour.dedup();
+        if contour.len() > 1 && contour.first() == contour.last() {
+            contour.pop();
+        }
+        if contour.len() >= 3 {
+            out.push(contour);
+        }
+    }
+    out
+}
+
+/// indexed triangle mesh, anticlockwise winding facing outward
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Mesh {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub indices: Vec<u32>,
+}
+
+/// samples f on a grid of `n[0] * n[1] * n[2]` points spanning min..max, x fastest then y then z
+pub fn sample_grid3(f: impl Fn(Vec3) -> f32, n: [usize; 3], min: Vec3, max: Vec3) -> Vec<f32> {
+    let mut out = Vec::with_capacity(n[0] * n[1] * n[2]);
+    for k in 0..n[2] {
+        for j in 0..n[1] {
+            for i in 0..n[0] {
+                out.push(f(grid_point3(n, min, max, [i, j, k])));
+            }
+        }
+    }
+    out
+}
+
+fn grid_point3(n: [usize; 3], min: Vec3, max: Vec3, p: [usize; 3]) -> Vec3 {
+    let d = vec3((n[0].max(2) - 1) as f32, (n[1].max(2) - 1) as f32, (n[2].max(2) - 1) as f32);
+    min + (max - min) * (vec3(p[0] as f32, p[1] as f32, p[2] as f32) / d)
+}
+
+/// a cube edge as the grid points at either end, in the order a face walks it
+type CubeEdge = ([usize; 3], [usize; 3]);
+
+/// cube faces as corner offsets, anticlockwise seen from outside
+const CUBE_FACES: [[[usize; 3]; 4]; 6] = [
+    [[0, 0, 0], [0, 0, 1], [0, 1, 1], [0, 1, 0]],
+    [[1, 0, 0], [1, 1, 0], [1, 1, 1], [1, 0, 1]],
+    [[0, 0, 0], [1, 0, 0], [1, 0, 1], [0, 0, 1]],
+    [[0, 1, 0], [0, 1, 1], [1, 1, 1], [1, 1, 0]],
+    [[0, 0, 0], [0, 1, 0], [1, 1, 0], [1, 0, 0]],
+    [[0, 0, 1], [1, 0, 1], [1, 1, 1], [0, 1, 1]],
+];
+
+/// iso surface of a 3d scalar field, normals from the field gradient.
+/// each cube face is split like a marching squares cell and the face segments are joined up into polygons,
+/// so neighbouring cubes always agree about saddles and the surface has no cracks
+pub fn marching_cubes(samples: &[f32], n: [usize; 3], min: Vec3, max: Vec3, iso: f32) -> Mesh {
+    assert_eq!(samples.len(), n[0] * n[1] * n[2]);
+    let index = |p: [usize; 3]| (p[2] * n[1] + p[1]) * n[0] + p[0];
+    let value = |p: [usize; 3]| samples[index(p)];
+    let gradient = |p: [usize; 3]| {
+        let mut g = [0.0; 3];
+        for (axis, g) in g.iter_mut().enumerate() {
+            let (mut lo, mut hi) = (p, p);
+            lo[axis] = p[axis].saturating_sub(1);
+            hi[axis] = (p[axis] + 1).min(n[axis] - 1);
+            let step = grid_point3(n, min, max, hi) - grid_point3(n, min, max, lo);
+            let len = [step.x, step.y, step.z][axis];
+            *g = if len == 0.0 { 0.0 } else { (value(hi) - value(lo)) / len };
+        }
+        vec3(g[0], g[1], g[2])
+    };
+
+    let mut mesh = Mesh::default();
+    let mut vertex_of_edge: BTreeMap<usize, u32> = BTreeMap::new();
+    let mut vertex = |a: [usize; 3], b: [usize; 3], mesh: &mut Mesh| -> u32 {
+        let (a, b) = if index(a) < index(b) { (a, b) } else { (b, a) };
+        let axis = (0..3).find(|&i| a[i] != b[i]).unwrap();
+        *vertex_of_edge.entry(3 * index(a) + axis).or_insert_with(|| {
+            let (va, vb) = (value(a), value(b));
+            let t = (iso - va) / (vb - va);
+            let (pa, pb) = (grid_point3(n, min, max, a), grid_point3(n, min, max, b));
+            let (ga, gb) = (gradient(a), gradient(b));
+            mesh.positions.push(pa + (pb - pa) * t);
+            mesh.normals.push((ga + (gb - ga) * t).unit().unwrap_or(vec3(0.0, 0.0, 0.0)));
+            mesh.positions.len() as u32 - 1
+        })
+    };
+
+    for k in 0..n[2].saturating_sub(1) {
+        for j in 0..n[1].saturating_sub(1) {
+            for i in 0..n[0].saturating_sub(1) {
+                let corner = |c: [usize; 3]| [i + c[0], j + c[1], k + c[2]];
+                let inside = (0..8).filter(|c| value(corner([c & 1, (c >> 1) & 1, c >> 2])) < iso).count();
+                if inside == 0 || inside == 8 {
+                    continue;
+                }
+                // face segments keyed by cube edge, as (from edge, to edge)
+                let mut next: Vec<(CubeEdge, CubeEdge)> = Vec::new();
+                for face in CUBE_FACES.iter() {
+                    let corners = face.map(corner);
+                    let values = corners.map(value);
+                    let edge = |e: usize| (corners[e], corners[(e + 1) % 4]);
+                    for (a, b) in cell_segments(values.map(|v| v < iso), values, iso) {
+                        next.push((edge(a), edge(b)));
+                    }
+                }
+                // chain into polygons. an edge entered from one face is left from the other face, going the other way
+                while let Some((start, mut e)) = next.pop() {
+                    let mut poly = vec![vertex(start.0, start.1, &mut mesh)];
+                    while e != (start.1, start.0) {
+                        poly.push(vertex(e.0, e.1, &mut mesh));
+                        let i = next.iter().position(|s| s.0 == (e.1, e.0)).unwrap();
+                        e = next.swap_remove(i).1;
+                    }
+                    // walking exit -> entry goes clockwise seen from outside, so fan the other way
+                    for w in 1..poly.len().saturating_sub(1) {
+                        mesh.indices.extend_from_slice(&[poly[0], poly[w + 1], poly[w]]);
+                    }
+                }
+            }
+        }
+    }
+    mesh
+}
+
+#[cfg(test)]
+fn signed_area(c: &[Vec2]) -> f32 {
+    (0..c.len()).map(|i| c[i].cross(c[(i + 1) % c.len()])).sum::<f32>() / 2.0
+}
+
+#[test]
+fn test_marching_squares_circle() {
+    let bounds = rect(-2.0, -2.0, 4.0, 4.0);
+    let s = sample_grid2(|p| sdf2_circle(p, 1.0), bounds, 41, 41);
+    let c = marching_squares(&s, 41, 41, bounds, 0.0);
+    assert_eq!(c.len(), 1);
+    for p in c[0].iter() {
+        assert!((p.norm() - 1.0).abs() < 0.01);
+    }
+    // anticlockwise around the inside
+    assert!((signed_area(&c[0]) - core::f32::consts::PI).abs() < 0.05);
+    // iso picks a different level set
+    let c = marching_squares(&s, 41, 41, bounds, 0.5);
+    assert!((c[0][0].norm() - 1.5).abs() < 0.01);
+}
+
+#[test]
+fn test_marching_squares_two_blobs_and_holes() {
+    let bounds = rect(-4.0, -2.0, 8.0, 4.0);
+    let f = Sdf2::circle(1.0).translate(vec2(-2.0, 0.0)).union(Sdf2::ring(1.0, 0.3).translate(vec2(2.0, 0.0)));
+    let s = sample_grid2(|p| f.eval(p), bounds, 81, 41);
+    let c = marching_squares(&s, 81, 41, bounds, 0.0);
+    // circle, outside of the ring, inside of the ring
+    assert_eq!(c.len(), 3);
+    let mut areas: Vec<f32> = c.iter().map(|c| signed_area(c)).collect();
+    areas.sort_by(|a, b| a.total_cmp(b));
+    // the hole goes clockwise
+    assert!(areas[0] < 0.0 && areas[1] > 0.0 && areas[2] > 0.0);
+}
+
+#[test]
+fn test_marching_squares_closes_at_boundary() {
+    // everything left of x = 0.5 is inside
+    let bounds = rect(0.0, 0.0, 1.0, 1.0);
+    let s = sample_grid2(|p| p.x - 0.5, bounds, 11, 11);
+    let c = marching_squares(&s, 11, 11, bounds, 0.0);
+    assert_eq!(c.len(), 1);
+    assert!((signed_area(&c[0]) - 0.5).abs() < 1e-4);
+}
+
+#[test]
+fn test_marching_squares_saddle() {
+    let bounds = rect(0.0, 0.0, 1.0, 1.0);
+    // opposite corners inside, centre average decides if they join
+    let split = marching_squares(&[-1.0, 1.0, 2.0, -1.0], 2, 2, bounds, 0.0);
+    assert_eq!(split.len(), 2);
+    let joined = marching_squares(&[-2.0, 1.0, 1.0, -2.0], 2, 2, bounds, 0.0);
+    assert_eq!(joined.len(), 1);
+}
+
+#[test]
+fn test_marching_cubes_sphere() {
+    let (min, max) = (vec3(-1.5, -1.5, -1.5), vec3(1.5, 1.5, 1.5));
+    let n = [25, 25, 25];
+    let s = sample_grid3(|p| sdf3_sphere(p, 1.0), n, min, max);
+    let m = marching_cubes(&s, n, min, max, 0.0);
+    assert!(!m.indices.is_empty());
+    assert_eq!(m.positions.len(), m.normals.len());
+    for (p, nrm) in m.positions.iter().zip(m.normals.iter()) {
+        assert!((p.norm() - 1.0).abs() < 0.02);
+        assert!((*nrm - p.unit().unwrap()).norm() < 0.05);
+    }
+    let mut edges: BTreeMap<(u32, u32), i32> = BTreeMap::new();
+    for t in m.indices.chunks(3) {
+        let (a, b, c) = (m.positions[t[0] as usize], m.positions[t[1] as usize], m.positions[t[2] as usize]);
+        // faces outward
+        assert!((b - a).cross(c - a).dot(a + b + c) >= 0.0);
+        for (u, v) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+            *edges.entry((u.min(v), u.max(v))).or_insert(0) += if u < v { 1 } else { -1 };
+        }
+    }
+    // closed and consistently wound: every edge used once in each direction
+    assert!(edges.values().all(|&c| c == 0));
+}
+
+#[test]
+fn test_marching_cubes_saddles_watertight() {
+    // a lumpy field with lots of ambiguous faces
+    let (min, max) = (vec3(0.0, 0.0, 0.0), vec3(1.0, 1.0, 1.0));
+    let n = [9, 9, 9];
+    let s = sample_grid3(|p| (p.x * 17.0).sin() * (p.y * 13.0).cos() + (p.z * 11.0).sin() * 0.7 - 0.1, n, min, max);
+    let m = marching_cubes(&s, n, min, max, 0.0);
+    let mut edges: BTreeMap<(u32, u32), i32> = BTreeMap::new();
+    for t in m.indices.chunks(3) {
+        for (u, v) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+            *edges.entry((u.min(v), u.max(v))).or_insert(0) += if u < v { 1 } else { -1 };
+        }
+    }
+    // edges lying on the boundary of the box are the only ones allowed to be unpaired
+    let on_boundary = |p: Vec3| [p.x, p.y, p.z].iter().any(|c| *c == 0.0 || *c == 1.0);
+    for ((u, v), c) in edges {
+        if c != 0 {
+            assert!(on_boundary(m.positions[u as usize]) && on_boundary(m.positions[v as usize]));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::*;
 
+#[allow(clippy::let_and_return)]
 pub fn mat3_inv(mat: &[f32; 9]) -> [f32; 9] {
     let a = mat[0];
     let b = mat[1];
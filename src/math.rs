@@ -0,0 +1,114 @@
+//! float math for no_std builds. with std the inherent methods take precedence, so
+//! call sites stay `x.sqrt()` either way and this trait only fills in the gaps, via libm
+#![cfg(feature = "libm")]
+// with std as well nothing calls it, see lib.rs
+#![cfg_attr(feature = "std", allow(dead_code))]
+
+pub(crate) trait FloatMath: Sized {
+    fn sqrt(self) -> Self;
+    fn cbrt(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn log2(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn sin_cos(self) -> (Self, Self);
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn trunc(self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_float_math {
+    ($t:ty, $sqrt:ident, $cbrt:ident, $pow:ident, $exp:ident, $log:ident, $log2:ident,
+     $sin:ident, $cos:ident, $tan:ident, $sincos:ident, $acos:ident, $atan2:ident,
+     $hypot:ident, $floor:ident, $ceil:ident, $round:ident, $trunc:ident) => {
+        impl FloatMath for $t {
+            fn sqrt(self) -> $t { libm::$sqrt(self) }
+            fn cbrt(self) -> $t { libm::$cbrt(self) }
+            fn powf(self, n: $t) -> $t { libm::$pow(self, n) }
+            fn powi(self, n: i32) -> $t {
+                let (mut base, mut e, mut acc) = (self, n.unsigned_abs(), 1.0);
+                while e > 0 {
+                    if e & 1 == 1 { acc *= base; }
+                    base *= base;
+                    e >>= 1;
+                }
+                if n < 0 { 1.0 / acc } else { acc }
+            }
+            fn exp(self) -> $t { libm::$exp(self) }
+            fn ln(self) -> $t { libm::$log(self) }
+            fn log2(self) -> $t { libm::$log2(self) }
+            fn sin(self) -> $t { libm::$sin(self) }
+            fn cos(self) -> $t { libm::$cos(self) }
+            fn tan(self) -> $t { libm::$tan(self) }
+            fn sin_cos(self) -> ($t, $t) { libm::$sincos(self) }
+            fn acos(self) -> $t { libm::$acos(self) }
+            fn atan2(self, other: $t) -> $t { libm::$atan2(self, other) }
+            fn hypot(self, other: $t) -> $t { libm::$hypot(self, other) }
+            fn floor(self) -> $t { libm::$floor(self) }
+            fn ceil(self) -> $t { libm::$ceil(self) }
+            fn round(self) -> $t { libm::$round(self) }
+            fn trunc(self) -> $t { libm::$trunc(self) }
+            fn rem_euclid(self, rhs: $t) -> $t {
+                let r = self % rhs;
+                if r < 0.0 { r + rhs.abs() } else { r }
+            }
+        }
+    };
+}
+
+impl_float_math!(f32, sqrtf, cbrtf, powf, expf, logf, log2f, sinf, cosf, tanf, sincosf,
+    acosf, atan2f, hypotf, floorf, ceilf, roundf, truncf);
+impl_float_math!(f64, sqrt, cbrt, pow, exp, log, log2, sin, cos, tan, sincos,
+    acos, atan2, hypot, floor, ceil, round, trunc);
+
+// the test harness always links std, so these call the trait explicitly and check it against the inherent methods
+#[cfg(test)]
+macro_rules! test_float_math {
+    ($name:ident, $t:ty, $tol:expr) => {
+        #[test]
+        fn $name() {
+            let close = |a: $t, b: $t| a == b || (a - b).abs() <= $tol * b.abs().max(1.0) || (a.is_nan() && b.is_nan());
+            let xs: [$t; 9] = [0.0, 0.5, 1.0, 2.5, -0.75, -3.5, 7.25, 100.0, -1e-3];
+            for &x in &xs {
+                let a = x.abs();
+                assert!(close(<$t as FloatMath>::sqrt(a), a.sqrt()));
+                assert!(close(<$t as FloatMath>::cbrt(x), x.cbrt()));
+                assert!(close(<$t as FloatMath>::exp(x.min(10.0)), x.min(10.0).exp()));
+                assert!(close(<$t as FloatMath>::ln(a + 0.1), (a + 0.1).ln()));
+                assert!(close(<$t as FloatMath>::log2(a + 0.1), (a + 0.1).log2()));
+                assert!(close(<$t as FloatMath>::sin(x), x.sin()));
+                assert!(close(<$t as FloatMath>::cos(x), x.cos()));
+                assert!(close(<$t as FloatMath>::tan(x), x.tan()));
+                let (s, c) = <$t as FloatMath>::sin_cos(x);
+                assert!(close(s, x.sin()) && close(c, x.cos()));
+                assert!(close(<$t as FloatMath>::acos(x.clamp(-1.0, 1.0)), x.clamp(-1.0, 1.0).acos()));
+                assert!(close(<$t as FloatMath>::atan2(x, 1.5), x.atan2(1.5)));
+                assert!(close(<$t as FloatMath>::hypot(x, 2.0), x.hypot(2.0)));
+                assert!(close(<$t as FloatMath>::powf(a, 1.7), a.powf(1.7)));
+                for n in [-3, -1, 0, 1, 2, 5] {
+                    assert!(close(<$t as FloatMath>::powi(x, n), x.powi(n)));
+                }
+                assert_eq!(<$t as FloatMath>::floor(x), x.floor());
+                assert_eq!(<$t as FloatMath>::ceil(x), x.ceil());
+                assert_eq!(<$t as FloatMath>::round(x), x.round());
+                assert_eq!(<$t as FloatMath>::trunc(x), x.trunc());
+                assert_eq!(<$t as FloatMath>::rem_euclid(x, 2.0), x.rem_euclid(2.0));
+                assert_eq!(<$t as FloatMath>::rem_euclid(x, -2.0), x.rem_euclid(-2.0));
+            }
+            assert_eq!(<$t as FloatMath>::round(-2.5), -3.0);
+        }
+    };
+}
+#[cfg(test)]
+test_float_math!(test_float_math_f32, f32, 1e-6);
+#[cfg(test)]
+test_float_math!(test_float_math_f64, f64, 1e-14);
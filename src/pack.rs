@@ -0,0 +1,263 @@
+use crate::*;
+
+// compact encodings for vertex attributes. the snorm/unorm rules follow
+// opengl / vulkan: unorm maps [0, 1] to 0..=max, snorm maps [-1, 1] to
+// -max..=max with the most negative integer also decoding to -1
+
+/// f32 to ieee half, rounding to nearest even. too big goes to infinity, too small to zero
+pub fn f32_to_f16(f: f32) -> u16 {
+    let x = f.to_bits();
+    let sign = ((x >> 16) & 0x8000) as u16;
+    let exp = ((x >> 23) & 0xff) as i32;
+    let man = x & 0x7f_ffff;
+    if exp == 0xff {
+        // keep nans nan by setting the quiet bit
+        return sign | 0x7c00 | if man != 0 { 0x200 | (man >> 13) as u16 } else { 0 };
+    }
+    let e = exp - 127 + 15;
+    if e >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if e <= 0 {
+        // subnormal half, the implicit 1 becomes explicit and shifts down
+        if e < -10 { return sign; }
+        let m = man | 0x80_0000;
+        let shift = (14 - e) as u32;
+        let (h, rem, halfway) = (m >> shift, m & ((1 << shift) - 1), 1 << (shift - 1));
+        let round = rem > halfway || (rem == halfway && h & 1 == 1);
+        return sign | (h + round as u32) as u16;
+    }
+    let h = ((e as u32) << 10) | (man >> 13);
+    let rem = man & 0x1fff;
+    // a carry out of the mantissa bumps the exponent, which is what we want, up to infinity
+    let round = rem > 0x1000 || (rem == 0x1000 && h & 1 == 1);
+    sign | (h + round as u32) as u16
+}
+
+/// ieee half to f32, exact
+pub fn f16_to_f32(h: u16) -> f32 {
+    let sign = ((h & 0x8000) as u32) << 16;
+    let exp = ((h >> 10) & 0x1f) as u32;
+    let man = (h & 0x3ff) as u32;
+    match exp {
+        0 => {
+            let v = man as f32 / (1 << 24) as f32;
+            if sign != 0 { -v } else { v }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (man << 13)),
+        _ => f32::from_bits(sign | ((exp + 112) << 23) | (man << 13)),
+    }
+}
+
+pub fn pack_unorm8(x: f32) -> u8 { (x.clamp(0.0, 1.0) * 255.0).round() as u8 }
+pub fn unpack_unorm8(x: u8) -> f32 { x as f32 / 255.0 }
+pub fn pack_unorm16(x: f32) -> u16 { (x.clamp(0.0, 1.0) * 65535.0).round() as u16 }
+pub fn unpack_unorm16(x: u16) -> f32 { x as f32 / 65535.0 }
+pub fn pack_snorm8(x: f32) -> i8 { (x.clamp(-1.0, 1.0) * 127.0).round() as i8 }
+pub fn unpack_snorm8(x: i8) -> f32 { (x as f32 / 127.0).max(-1.0) }
+pub fn pack_snorm16(x: f32) -> i16 { (x.clamp(-1.0, 1.0) * 32767.0).round() as i16 }
+pub fn unpack_snorm16(x: i16) -> f32 { (x as f32 / 32767.0).max(-1.0) }
+
+macro_rules! impl_pack {
+    ($t:ident, $n:literal { $($f:ident),+ }) => {
+        impl $t {
+            pub fn to_f16(&self) -> [u16; $n] { [$(f32_to_f16(self.$f)),+] }
+            pub fn from_f16(h: [u16; $n]) -> $t { let [$($f),+] = h; $t { $($f: f16_to_f32($f)),+ } }
+            pub fn to_unorm8(&self) -> [u8; $n] { [$(pack_unorm8(self.$f)),+] }
+            pub fn from_unorm8(v: [u8; $n]) -> $t { let [$($f),+] = v; $t { $($f: unpack_unorm8($f)),+ } }
+            pub fn to_unorm16(&self) -> [u16; $n] { [$(pack_unorm16(self.$f)),+] }
+            pub fn from_unorm16(v: [u16; $n]) -> $t { let [$($f),+] = v; $t { $($f: unpack_unorm16($f)),+ } }
+            pub fn to_snorm8(&self) -> [i8; $n] { [$(pack_snorm8(self.$f)),+] }
+            pub fn from_snorm8(v: [i8; $n]) -> $t { let [$($f),+] = v; $t { $($f: unpack_snorm8($f)),+ } }
+            pub fn to_snorm16(&self) -> [i16; $n] { [$(pack_snorm16(self.$f)),+] }
+            pub fn from_snorm16(v: [i16; $n]) -> $t { let [$($f),+] = v; $t { $($f: unpack_snorm16($f)),+ } }
+        }
+    };
+}
+
+impl_pack!(Vec2, 2 { x, y });
+impl_pack!(Vec3, 3 { x, y, z });
+impl_pack!(Vec4, 4 { x, y, z, w });
+
+/// 1 for zero too, so the octahedral fold has no dead spot
+fn sign_not_zero(x: f32) -> f32 { if x >= 0.0 { 1.0 } else { -1.0 } }
+
+impl Vec3 {
+    /// unit vector to a point in [-1, 1]^2 by projecting onto an octahedron and unfolding it
+    pub fn oct_encode(&self) -> Vec2 {
+        let n = *self / (self.x.abs() + self.y.abs() + self.z.abs());
+        if n.z >= 0.0 {
+            vec2(n.x, n.y)
+        } else {
+            vec2((1.0 - n.y.abs()) * sign_not_zero(n.x), (1.0 - n.x.abs()) * sign_not_zero(n.y))
+        }
+    }
+    /// oct_encode into two snorm16s, under 0.01 degrees of error
+    pub fn oct_encode_snorm16(&self) -> [i16; 2] { self.oct_encode().to_snorm16() }
+    pub fn oct_decode_snorm16(v: [i16; 2]) -> Vec3 { Vec2::from_snorm16(v).oct_decode() }
+}
+
+impl Vec2 {
+    /// unit vector back from oct_encode
+    pub fn oct_decode(&self) -> Vec3 {
+        let z = 1.0 - self.x.abs() - self.y.abs();
+        let t = (-z).max(0.0);
+        let n = vec3(self.x - t * sign_not_zero(self.x), self.y - t * sign_not_zero(self.y), z);
+        n / n.norm()
+    }
+}
+
+impl Vec4 {
+    /// rgb in 10 bits each and alpha in 2, red in the low bits. the GL_UNSIGNED_INT_2_10_10_10_REV layout
+    pub fn to_rgb10a2(&self) -> u32 {
+        let q = |c: f32, max: f32| (c.clamp(0.0, 1.0) * max).round() as u32;
+        q(self.x, 1023.0) | q(self.y, 1023.0) << 10 | q(self.z, 1023.0) << 20 | q(self.w, 3.0) << 30
+    }
+    pub fn from_rgb10a2(p: u32) -> Vec4 {
+        let c = |shift: u32| ((p >> shift) & 0x3ff) as f32 / 1023.0;
+        vec4(c(0), c(10), c(20), (p >> 30) as f32 / 3.0)
+    }
+    /// unit quaternion (x, y, z, w) in 32 bits: which component is largest in 2 bits, the other
+    /// three in 10 bits each. q and -q are the same rotation so the largest is made positive
+    pub fn pack_quat_smallest_three(&self) -> u32 {
+        let c = [self.x, self.y, self.z, self.w];
+        let largest = (0..4).fold(0, |m, i| if c[i].abs() > c[m].abs() { i } else { m });
+        let sign = sign_not_zero(c[largest]);
+        // the rest are at most 1/sqrt 2 in size
+        let range = core::f32::consts::FRAC_1_SQRT_2;
+        let mut p = largest as u32;
+        for i in (0..4).filter(|&i| i != largest) {
+            // 0..=1022 rather than 1023 so that zero is exact
+            let x = (c[i] * sign / range).clamp(-1.0, 1.0);
+            p = p << 10 | (x * 511.0 + 511.0).round() as u32;
+        }
+        p
+    }
+    pub fn unpack_quat_smallest_three(p: u32) -> Vec4 {
+        let largest = (p >> 30) as usize;
+        let range = core::f32::consts::FRAC_1_SQRT_2;
+        let mut c = [0.0; 4];
+        let mut shift = 20;
+        for i in (0..4).filter(|&i| i != largest) {
+            c[i] = ((((p >> shift) & 0x3ff) as f32 - 511.0) / 511.0).min(1.0) * range;
+            shift -= 10;
+        }
+        let rest: f32 = c.iter().map(|x| x * x).sum();
+        c[largest] = (1.0 - rest).max(0.0).sqrt();
+        vec4(c[0], c[1], c[2], c[3])
+    }
+}
+
+#[test]
+fn test_f16() {
+    assert_eq!(f32_to_f16(0.0), 0);
+    assert_eq!(f32_to_f16(-0.0), 0x8000);
+    assert_eq!(f32_to_f16(1.0), 0x3c00);
+    assert_eq!(f32_to_f16(-2.0), 0xc000);
+    assert_eq!(f32_to_f16(65504.0), 0x7bff);
+    assert_eq!(f32_to_f16(65520.0), 0x7c00);
+    assert_eq!(f32_to_f16(f32::INFINITY), 0x7c00);
+    assert_eq!(f32_to_f16(5.9604645e-8), 0x0001);
+    assert_eq!(f32_to_f16(1e-8), 0);
+    assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+    // ties go to even: 1 + 2^-11 is half way between 1 and the next half
+    assert_eq!(f32_to_f16(1.0 + 1.0 / 2048.0), 0x3c00);
+    assert_eq!(f32_to_f16(1.0 + 3.0 / 2048.0), 0x3c02);
+    // every half survives the trip through f32
+    for h in 0..=u16::MAX {
+        let f = f16_to_f32(h);
+        if !f.is_nan() { assert_eq!(f32_to_f16(f), h, "{:04x}", h); }
+    }
+    // normal range values are within half an ulp, 2^-11 relative
+    for i in 0..10000 {
+        let f = (i as f32 * 0.737).sin() * 1000.0 + 0.01;
+        let back = f16_to_f32(f32_to_f16(f));
+        assert!(((back - f) / f).abs() <= 1.0 / 2048.0, "{}", f);
+    }
+    let v = vec3(0.1, -2.5, 1000.0);
+    assert!((Vec3::from_f16(v.to_f16()) - v).abs().max_element() < 0.5);
+    assert_eq!(Vec2::from_f16(vec2(1.0, -2.0).to_f16()), vec2(1.0, -2.0));
+}
+
+#[test]
+fn test_norm_packing() {
+    assert_eq!(pack_unorm8(1.0), 255);
+    assert_eq!(pack_unorm8(0.5), 128);
+    assert_eq!(pack_unorm8(2.0), 255);
+    assert_eq!(pack_snorm8(-1.0), -127);
+    assert_eq!(pack_snorm16(1.0), 32767);
+    assert_eq!(unpack_snorm8(-128), -1.0);
+    assert_eq!(unpack_snorm16(i16::MIN), -1.0);
+    assert_eq!(unpack_snorm8(0), 0.0);
+    for i in 0..=1000 {
+        let x = i as f32 / 1000.0;
+        assert!((unpack_unorm8(pack_unorm8(x)) - x).abs() <= 0.5 / 255.0 + 1e-7);
+        assert!((unpack_unorm16(pack_unorm16(x)) - x).abs() <= 0.5 / 65535.0 + 1e-7);
+        let s = x * 2.0 - 1.0;
+        assert!((unpack_snorm8(pack_snorm8(s)) - s).abs() <= 0.5 / 127.0 + 1e-7);
+        assert!((unpack_snorm16(pack_snorm16(s)) - s).abs() <= 0.5 / 32767.0 + 1e-7);
+    }
+    let v = vec4(0.1, 0.2, 0.9, 1.0);
+    assert!((Vec4::from_unorm8(v.to_unorm8()) - v).abs().max_element() <= 0.5 / 255.0 + 1e-7);
+    assert!((Vec4::from_unorm16(v.to_unorm16()) - v).abs().max_element() <= 0.5 / 65535.0);
+    let n = vec3(-0.3, 0.5, -1.0);
+    assert!((Vec3::from_snorm8(n.to_snorm8()) - n).abs().max_element() <= 0.5 / 127.0);
+    assert!((Vec3::from_snorm16(n.to_snorm16()) - n).abs().max_element() <= 0.5 / 32767.0);
+}
+
+#[test]
+fn test_rgb10a2() {
+    let c = vec4(1.0, 0.0, 0.5, 1.0);
+    assert_eq!(c.to_rgb10a2(), 0x3ff | 512 << 20 | 3 << 30);
+    for i in 0..100 {
+        let t = i as f32 / 99.0;
+        let c = vec4(t, 1.0 - t, (t * 7.0).fract(), (i % 4) as f32 / 3.0);
+        let back = Vec4::from_rgb10a2(c.to_rgb10a2());
+        assert!((back.truncate() - c.truncate()).abs().max_element() <= 0.5 / 1023.0 + 1e-7);
+        assert_eq!(back.w, c.w);
+    }
+}
+
+#[cfg(test)]
+fn test_unit_vectors() -> Vec<Vec3> {
+    let mut v = vec![vec3(0.0, 0.0, 1.0), vec3(0.0, 0.0, -1.0), vec3(1.0, 0.0, 0.0), vec3(0.0, -1.0, 0.0)];
+    // fibonacci sphere
+    let n = 2000;
+    for i in 0..n {
+        let z = 1.0 - 2.0 * (i as f32 + 0.5) / n as f32;
+        let r = (1.0 - z * z).sqrt();
+        let a = i as f32 * 2.399_963;
+        v.push(vec3(r * a.cos(), r * a.sin(), z));
+    }
+    v
+}
+
+#[test]
+fn test_octahedral() {
+    for n in test_unit_vectors() {
+        let e = n.oct_encode();
+        assert!(e.x.abs() <= 1.0 && e.y.abs() <= 1.0);
+        assert!((e.oct_decode() - n).norm() < 1e-5, "{}", n);
+        let d = Vec3::oct_decode_snorm16(n.oct_encode_snorm16());
+        assert!((d - n).norm() < 1e-4, "{} {}", n, d);
+        assert!((d.norm() - 1.0).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_quat_smallest_three() {
+    for (i, axis) in test_unit_vectors().into_iter().enumerate().step_by(7) {
+        let half = i as f32 * 0.1;
+        let (s, c) = half.sin_cos();
+        let q = (axis * s).extend(c);
+        let back = Vec4::unpack_quat_smallest_three(q.pack_quat_smallest_three());
+        // same rotation either sign
+        let err = (back - q).abs().max_element().min((back + q).abs().max_element());
+        assert!(err < 2e-3, "{} {}", q, back);
+        assert!(back.dot(q).abs() > 0.99999);
+    }
+    let id = vec4(0.0, 0.0, 0.0, 1.0);
+    assert!((Vec4::unpack_quat_smallest_three(id.pack_quat_smallest_three()) - id).norm() < 1e-6);
+    let neg = vec4(0.0, 0.0, 0.0, -1.0);
+    assert!((Vec4::unpack_quat_smallest_three(neg.pack_quat_smallest_three()) - id).norm() < 1e-6);
+}
@@ -0,0 +1,256 @@
+use crate::*;
+
+/// one piece of a path: a straight segment or a bezier curve, in any dimension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve<V> {
+    Line(V, V),
+    Quadratic(V, V, V),
+    Cubic(V, V, V, V),
+}
+
+impl<V: Vector> Curve<V> {
+    pub fn start(&self) -> V {
+        match *self {
+            Curve::Line(a, _) | Curve::Quadratic(a, _, _) | Curve::Cubic(a, _, _, _) => a,
+        }
+    }
+    pub fn end(&self) -> V {
+        match *self {
+            Curve::Line(_, b) | Curve::Quadratic(_, _, b) | Curve::Cubic(_, _, _, b) => b,
+        }
+    }
+    /// point at parameter t in [0, 1]
+    pub fn point(&self, t: f32) -> V {
+        let s = 1.0 - t;
+        match *self {
+            Curve::Line(a, b) => a * s + b * t,
+            Curve::Quadratic(a, b, c) => a * (s*s) + b * (2.0*s*t) + c * (t*t),
+            Curve::Cubic(a, b, c, d) => a * (s*s*s) + b * (3.0*s*s*t) + c * (3.0*s*t*t) + d * (t*t*t),
+        }
+    }
+    /// derivative wrt t, not normalized
+    pub fn derivative(&self, t: f32) -> V {
+        let s = 1.0 - t;
+        match *self {
+            Curve::Line(a, b) => b - a,
+            Curve::Quadratic(a, b, c) => (b - a) * (2.0*s) + (c - b) * (2.0*t),
+            Curve::Cubic(a, b, c, d) => (b - a) * (3.0*s*s) + (c - b) * (6.0*s*t) + (d - c) * (3.0*t*t),
+        }
+    }
+    /// unit tangent at t. falls back to a finite difference where the derivative vanishes, eg at a cubic with doubled up control points
+    pub fn tangent(&self, t: f32) -> V {
+        let d = self.derivative(t);
+        if d.norm() > 1e-6 {
+            return d / d.norm();
+        }
+        let h = 1e-3;
+        normalize_or_zero(self.point((t + h).min(1.0)) - self.point((t - h).max(0.0)))
+    }
+    /// appends points approximating the curve to within tol, not including the start point
+    pub fn flatten_into(&self, tol: f32, out: &mut Vec<V>) {
+        // chord error of m segments is at most max|B''| / (8 m^2)
+        let m = match *self {
+            Curve::Line(_, b) => { out.push(b); return; }
+            Curve::Quadratic(a, b, c) => 2.0 * (a - b * 2.0 + c).norm(),
+            Curve::Cubic(a, b, c, d) => 6.0 * (a - b * 2.0 + c).norm().max((b - c * 2.0 + d).norm()),
+        };
+        let n = ((m / (8.0 * tol.max(1e-6))).sqrt().ceil() as usize).clamp(1, 1024);
+        for i in 1..=n {
+            out.push(self.point(i as f32 / n as f32));
+        }
+    }
+}
+
+/// tangent and rotation minimizing normal at a point on a path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame<V> {
+    pub tangent: V,
+    pub normal: V,
+}
+
+impl Frame<Vec3> {
+    pub fn binormal(&self) -> Vec3 { self.tangent.cross(self.normal) }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Sample<V> {
+    dist: f32,
+    curve: usize,
+    t: f32,
+    point: V,
+    tangent: V,
+    normal: V,
+}
+
+/// a sequence of curves with an arc length lookup table, for moving along at constant speed.
+/// distances are clamped to [0, length]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path<V> {
+    curves: Vec<Curve<V>>,
+    samples: Vec<Sample<V>>,
+}
+
+impl<V: Vector> Path<V> {
+    /// samples each curve 64 times for the lookup table
+    pub fn new(curves: Vec<Curve<V>>) -> Self {
+        Self::with_resolution(curves, 64)
+    }
+    /// samples_per_curve trades memory and build time for accuracy of the distance -> t mapping
+    pub fn with_resolution(curves: Vec<Curve<V>>, samples_per_curve: usize) -> Self {
+        let n = samples_per_curve.max(1);
+        let mut samples: Vec<Sample<V>> = Vec::with_capacity(curves.len() * (n + 1));
+        for (i, c) in curves.iter().enumerate() {
+            for k in 0..=n {
+                let t = k as f32 / n as f32;
+                let point = c.point(t);
+                let tangent = c.tangent(t);
+                let (dist, normal) = match samples.last() {
+                    None => (0.0, normalize_or_zero(tangent.orthogonal())),
+                    // a gap between curves that don't join adds no distance, the path jumps over it
+                    Some(prev) if k == 0 => (prev.dist, transport_normal(prev.point, prev.tangent, prev.normal, point, tangent)),
+                    Some(prev) => (
+                        prev.dist + (point - prev.point).norm(),
+                        transport_normal(prev.point, prev.tangent, prev.normal, point, tangent),
+                    ),
+                };
+                samples.push(Sample { dist, curve: i, t, point, tangent, normal });
+            }
+        }
+        Path { curves, samples }
+    }
+    /// polyline through points
+    pub fn from_points(points: &[V]) -> Self {
+        Self::with_resolution(points.windows(2).map(|w| Curve::Line(w[0], w[1])).collect(), 1)
+    }
+    pub fn curves(&self) -> &[Curve<V>] { &self.curves }
+    pub fn length(&self) -> f32 { self.samples.last().map(|s| s.dist).unwrap_or(0.0) }
+    /// curve index and parameter at distance d along the path, None if there are no curves
+    pub fn locate(&self, d: f32) -> Option<(usize, f32)> {
+        let (a, b, u) = self.bracket(d)?;
+        Some((a.curve, a.t + (b.t - a.t) * u))
+    }
+    pub fn point_at_distance(&self, d: f32) -> V {
+        let Some((i, t)) = self.locate(d) else { return V::zero() };
+        self.curves[i].point(t)
+    }
+    /// unit tangent at distance d along the path
+    pub fn tangent_at_distance(&self, d: f32) -> V {
+        let Some((i, t)) = self.locate(d) else { return V::zero() };
+        self.curves[i].tangent(t)
+    }
+    /// rotation minimizing frame at distance d. the normal starts off as tangent.orthogonal(), which in 2d is the left hand side, and is then carried along without twisting
+    pub fn frame_at_distance(&self, d: f32) -> Frame<V> {
+        let Some((a, b, u)) = self.bracket(d) else { return Frame { tangent: V::zero(), normal: V::zero() } };
+        let c = &self.curves[a.curve];
+        let t = a.t + (b.t - a.t) * u;
+        let point = c.point(t);
+        let tangent = c.tangent(t);
+        let normal = transport_normal(a.point, a.tangent, a.normal, point, tangent);
+        Frame { tangent, normal }
+    }
+    /// samples either side of distance d and how far between them d is
+    fn bracket(&self, d: f32) -> Option<(Sample<V>, Sample<V>, f32)> {
+        let first = *self.samples.first()?;
+        let i = self.samples.partition_point(|s| s.dist < d);
+        if i == 0 {
+            return Some((first, first, 0.0));
+        }
+        if i == self.samples.len() {
+            let last = self.samples[i - 1];
+            return Some((last, last, 0.0));
+        }
+        let a = self.samples[i - 1];
+        let b = self.samples[i];
+        // d > a.dist and d <= b.dist, and the step from one curve to the next adds no distance,
+        // so a and b are always on the same curve
+        Some((a, b, (d - a.dist) / (b.dist - a.dist)))
+    }
+}
+
+/// double reflection method (Wang et al. 2008): carry normal r0 from (x0, t0) to (x1, t1) with minimal rotation
+fn transport_normal<V: Vector>(x0: V, t0: V, r0: V, x1: V, t1: V) -> V {
+    let reflect = |v: V, n: V| {
+        let c = n.dot(n);
+        if c == 0.0 { v } else { v - n * (2.0 * n.dot(v) / c) }
+    };
+    let v1 = x1 - x0;
+    let rl = reflect(r0, v1);
+    let tl = reflect(t0, v1);
+    let r1 = reflect(rl, t1 - tl);
+    // remove any drift out of the normal plane
+    let r1 = r1 - t1 * t1.dot(r1);
+    let r1 = normalize_or_zero(r1);
+    if r1 == V::zero() { normalize_or_zero(t1.orthogonal()) } else { r1 }
+}
+
+#[test]
+fn test_path_polyline_length() {
+    let p = Path::from_points(&[vec2(0.0, 0.0), vec2(3.0, 0.0), vec2(3.0, 4.0)]);
+    assert_eq!(p.length(), 7.0);
+    assert_eq!(p.point_at_distance(5.0), vec2(3.0, 2.0));
+    assert_eq!(p.tangent_at_distance(5.0), vec2(0.0, 1.0));
+    assert_eq!(p.point_at_distance(-1.0), vec2(0.0, 0.0));
+    assert_eq!(p.point_at_distance(100.0), vec2(3.0, 4.0));
+}
+
+#[test]
+fn test_path_constant_speed() {
+    // control points bunched at the start so t is very uneven in distance
+    let c = Curve::Cubic(vec2(0.0, 0.0), vec2(0.1, 0.0), vec2(0.2, 0.0), vec2(10.0, 0.0));
+    let p = Path::with_resolution(vec![c], 256);
+    assert!((p.length() - 10.0).abs() < 1e-3);
+    for i in 0..=10 {
+        let d = i as f32;
+        assert!((p.point_at_distance(d).x - d).abs() < 0.02);
+    }
+}
+
+#[test]
+fn test_path_frame_2d_is_left_normal() {
+    let c = Curve::Quadratic(vec2(0.0, 0.0), vec2(1.0, 1.0), vec2(2.0, 0.0));
+    let p = Path::new(vec![c]);
+    for i in 0..=10 {
+        let f = p.frame_at_distance(p.length() * i as f32 / 10.0);
+        let left = vec2(-f.tangent.y, f.tangent.x);
+        assert!((f.normal - left).norm() < 1e-4);
+    }
+}
+
+#[test]
+fn test_path_frame_3d_orthonormal_and_untwisted() {
+    // a helix-ish curve and a straight line
+    let c = Curve::Cubic(vec3(1.0, 0.0, 0.0), vec3(1.0, 1.0, 0.5), vec3(-1.0, 1.0, 1.0), vec3(-1.0, 0.0, 1.5));
+    let p = Path::new(vec![c, Curve::Line(vec3(-1.0, 0.0, 1.5), vec3(-1.0, 0.0, 5.0))]);
+    for i in 0..=20 {
+        let f = p.frame_at_distance(p.length() * i as f32 / 20.0);
+        let b = f.binormal();
+        assert!((f.tangent.norm() - 1.0).abs() < 1e-4);
+        assert!((f.normal.norm() - 1.0).abs() < 1e-4);
+        assert!(f.tangent.dot(f.normal).abs() < 1e-4);
+        assert!((b.norm() - 1.0).abs() < 1e-4);
+    }
+    // along the straight bit the frame must not rotate
+    let f0 = p.frame_at_distance(p.length() - 3.0);
+    let f1 = p.frame_at_distance(p.length());
+    assert!((f0.normal - f1.normal).norm() < 1e-4);
+}
+
+#[test]
+fn test_path_discontinuous_and_empty() {
+    let p = Path::with_resolution(vec![Curve::Line(vec2(0.0, 0.0), vec2(1.0, 0.0)), Curve::Line(vec2(3.0, 0.0), vec2(4.0, 0.0))], 4);
+    // the gap isn't part of the length
+    assert_eq!(p.length(), 2.0);
+    assert_eq!(p.point_at_distance(0.5), vec2(0.5, 0.0));
+    assert_eq!(p.point_at_distance(1.0), vec2(1.0, 0.0));
+    assert_eq!(p.point_at_distance(1.5), vec2(3.5, 0.0));
+    assert_eq!(p.locate(1.25), Some((1, 0.25)));
+    assert_eq!(p.tangent_at_distance(1.5), vec2(1.0, 0.0));
+    assert_eq!(p.point_at_distance(3.0), vec2(4.0, 0.0));
+
+    for p in [Path::new(vec![]), Path::from_points(&[vec2(1.0, 2.0)])] {
+        assert_eq!(p.locate(0.0), None);
+        assert_eq!(p.length(), 0.0);
+        assert_eq!(p.point_at_distance(1.0), vec2(0.0, 0.0));
+        assert_eq!(p.frame_at_distance(1.0).normal, vec2(0.0, 0.0));
+    }
+}
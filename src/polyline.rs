@@ -0,0 +1,223 @@
+use crate::*;
+use core::cmp::Ordering;
+use alloc::collections::BinaryHeap;
+
+/// total length of a polyline
+pub fn polyline_length<V: Vector>(points: &[V]) -> f32 {
+    points.windows(2).map(|w| (w[1] - w[0]).norm()).sum()
+}
+
+fn dist_to_segment<V: Vector>(p: V, a: V, b: V) -> f32 {
+    let ab = b - a;
+    let l2 = ab.dot(ab);
+    let t = if l2 == 0.0 { 0.0 } else { ((p - a).dot(ab) / l2).clamp(0.0, 1.0) };
+    (p - (a + ab * t)).norm()
+}
+
+fn triangle_area<V: Vector>(a: V, b: V, c: V) -> f32 {
+    // lagrange's identity so it works in any dimension
+    let u = b - a;
+    let v = c - a;
+    0.5 * (u.dot(u) * v.dot(v) - u.dot(v) * u.dot(v)).max(0.0).sqrt()
+}
+
+/// ramer douglas peucker: drops points that are within tol of the simplified line. keeps the endpoints
+pub fn polyline_simplify_rdp<V: Vector>(points: &[V], tol: f32) -> Vec<V> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    // explicit stack rather than recursion so long strokes cant overflow
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((a, b)) = stack.pop() {
+        let mut worst = 0.0;
+        let mut worst_i = a;
+        for (i, p) in points.iter().enumerate().take(b).skip(a + 1) {
+            let d = dist_to_segment(*p, points[a], points[b]);
+            if d > worst {
+                worst = d;
+                worst_i = i;
+            }
+        }
+        if worst > tol {
+            keep[worst_i] = true;
+            stack.push((a, worst_i));
+            stack.push((worst_i, b));
+        }
+    }
+    points.iter().zip(keep).filter(|(_, k)| *k).map(|(p, _)| *p).collect()
+}
+
+#[derive(PartialEq)]
+struct AreaEntry {
+    area: f32,
+    i: usize,
+}
+impl Eq for AreaEntry {}
+impl Ord for AreaEntry {
+    // reversed so the binary heap pops the smallest area first
+    fn cmp(&self, other: &Self) -> Ordering { other.area.total_cmp(&self.area).then(other.i.cmp(&self.i)) }
+}
+impl PartialOrd for AreaEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+/// visvalingam whyatt: repeatedly drops the point making the smallest triangle with its neighbours until every remaining triangle has at least min_area. keeps the endpoints
+pub fn polyline_simplify_vw<V: Vector>(points: &[V], min_area: f32) -> Vec<V> {
+    let n = points.len();
+    if n < 3 {
+        return points.to_vec();
+    }
+    let mut prev: Vec<usize> = (0..n).map(|i| i.wrapping_sub(1)).collect();
+    let mut next: Vec<usize> = (1..=n).collect();
+    let mut area = vec![f32::INFINITY; n];
+    let mut removed = vec![false; n];
+    let mut heap = BinaryHeap::new();
+    for i in 1..n - 1 {
+        area[i] = triangle_area(points[i - 1], points[i], points[i + 1]);
+        heap.push(AreaEntry { area: area[i], i });
+    }
+    // effective area never decreases, so a point can't be removed for less than the one before it
+    let mut floor = 0.0f32;
+    while let Some(AreaEntry { area: a, i }) = heap.pop() {
+        if removed[i] || a != area[i] {
+            continue;
+        }
+        if a >= min_area {
+            break;
+        }
+        floor = floor.max(a);
+        removed[i] = true;
+        let (p, q) = (prev[i], next[i]);
+        next[p] = q;
+        prev[q] = p;
+        for j in [p, q] {
+            if j != 0 && j != n - 1 {
+                area[j] = triangle_area(points[prev[j]], points[j], points[next[j]]).max(floor);
+                heap.push(AreaEntry { area: area[j], i: j });
+            }
+        }
+    }
+    points.iter().zip(removed).filter(|(_, r)| !*r).map(|(p, _)| *p).collect()
+}
+
+/// points evenly spaced along the polyline, starting at the first point. the last point is always included so the final gap may be short
+pub fn polyline_resample<V: Vector>(points: &[V], spacing: f32) -> Vec<V> {
+    let Some(&first) = points.first() else { return Vec::new() };
+    let mut out = vec![first];
+    if spacing <= 0.0 {
+        return out;
+    }
+    // distance along the current segment of the next output point
+    let mut next = spacing;
+    for w in points.windows(2) {
+        let l = (w[1] - w[0]).norm();
+        while next <= l {
+            out.push(w[0] + (w[1] - w[0]) * (next / l));
+            next += spacing;
+        }
+        next -= l;
+    }
+    let last = points[points.len() - 1];
+    if (last - out[out.len() - 1]).norm() > spacing * 1e-3 {
+        out.push(last);
+    }
+    out
+}
+
+/// chaikin corner cutting. each iteration replaces every segment with points at 1/4 and 3/4 along it. open polylines keep their endpoints
+pub fn polyline_chaikin<V: Vector>(points: &[V], iterations: usize, closed: bool) -> Vec<V> {
+    let mut pts = points.to_vec();
+    for _ in 0..iterations {
+        if pts.len() < 3 {
+            break;
+        }
+        let n = pts.len();
+        let segments = if closed { n } else { n - 1 };
+        let mut out = Vec::with_capacity(segments * 2 + 2);
+        if !closed {
+            out.push(pts[0]);
+        }
+        for i in 0..segments {
+            let a = pts[i];
+            let b = pts[(i + 1) % n];
+            out.push(a * 0.75 + b * 0.25);
+            out.push(a * 0.25 + b * 0.75);
+        }
+        if !closed {
+            out.push(pts[n - 1]);
+        }
+        pts = out;
+    }
+    pts
+}
+
+/// moving average over radius neighbours either side. the window shrinks near the ends so the endpoints stay put
+pub fn polyline_smooth<V: Vector>(points: &[V], radius: usize) -> Vec<V> {
+    let n = points.len();
+    (0..n).map(|i| {
+        let r = radius.min(i).min(n - 1 - i);
+        let window = &points[i - r..=i + r];
+        window.iter().fold(V::zero(), |acc, p| acc + *p) / window.len() as f32
+    }).collect()
+}
+
+#[test]
+fn test_polyline_rdp() {
+    let pts = vec![vec2(0.0, 0.0), vec2(1.0, 0.1), vec2(2.0, -0.1), vec2(3.0, 5.0), vec2(4.0, 6.0), vec2(5.0, 7.0)];
+    assert_eq!(polyline_simplify_rdp(&pts, 0.5), vec![vec2(0.0, 0.0), vec2(2.0, -0.1), vec2(3.0, 5.0), vec2(5.0, 7.0)]);
+    // (4, 6) is exactly on the line so it goes even with a tiny tolerance
+    assert_eq!(polyline_simplify_rdp(&pts, 0.01), vec![pts[0], pts[1], pts[2], pts[3], pts[5]]);
+    assert_eq!(polyline_simplify_rdp(&pts, 100.0), vec![pts[0], pts[5]]);
+    let pts3 = vec![vec3(0.0, 0.0, 0.0), vec3(1.0, 0.0, 0.01), vec3(2.0, 0.0, 0.0)];
+    assert_eq!(polyline_simplify_rdp(&pts3, 0.1), vec![pts3[0], pts3[2]]);
+}
+
+#[test]
+fn test_polyline_vw() {
+    let pts = vec![vec2(0.0, 0.0), vec2(1.0, 0.1), vec2(2.0, 0.0), vec2(3.0, 3.0), vec2(4.0, 0.0)];
+    // the bump at 1 has area 0.1, the spike at 3 has area 3
+    assert_eq!(polyline_simplify_vw(&pts, 0.5), vec![vec2(0.0, 0.0), vec2(2.0, 0.0), vec2(3.0, 3.0), vec2(4.0, 0.0)]);
+    assert_eq!(polyline_simplify_vw(&pts, 0.05), pts);
+    assert_eq!(polyline_simplify_vw(&pts, 100.0), vec![pts[0], pts[4]]);
+}
+
+#[test]
+fn test_polyline_resample() {
+    let pts = vec![vec2(0.0, 0.0), vec2(2.5, 0.0), vec2(2.5, 2.5)];
+    let r = polyline_resample(&pts, 1.0);
+    assert_eq!(r, vec![
+        vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(2.0, 0.0), vec2(2.5, 0.5),
+        vec2(2.5, 1.5), vec2(2.5, 2.5),
+    ]);
+    // straight runs come out exactly at the spacing
+    let r = polyline_resample(&[vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 10.0)], 3.0);
+    assert_eq!(r, vec![vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 3.0), vec3(0.0, 0.0, 6.0), vec3(0.0, 0.0, 9.0), vec3(0.0, 0.0, 10.0)]);
+}
+
+#[test]
+fn test_polyline_chaikin() {
+    let pts = vec![vec2(0.0, 0.0), vec2(4.0, 0.0), vec2(4.0, 4.0)];
+    let c = polyline_chaikin(&pts, 1, false);
+    assert_eq!(c, vec![vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(3.0, 0.0), vec2(4.0, 1.0), vec2(4.0, 3.0), vec2(4.0, 4.0)]);
+    let c = polyline_chaikin(&pts, 1, true);
+    assert_eq!(c.len(), 6);
+    assert_eq!(c[5], vec2(1.0, 1.0));
+    // converges, so more iterations shouldnt move things much
+    let a = polyline_chaikin(&pts, 5, false);
+    let b = polyline_chaikin(&pts, 6, false);
+    assert!((polyline_length(&a) - polyline_length(&b)).abs() < 0.01);
+}
+
+#[test]
+fn test_polyline_smooth() {
+    let pts = vec![vec3(0.0, 0.0, 0.0), vec3(1.0, 3.0, 0.0), vec3(2.0, 0.0, 0.0), vec3(3.0, 3.0, 0.0), vec3(4.0, 0.0, 0.0)];
+    let s = polyline_smooth(&pts, 1);
+    assert_eq!(s[0], pts[0]);
+    assert_eq!(s[4], pts[4]);
+    assert_eq!(s[1], vec3(1.0, 1.0, 0.0));
+    assert_eq!(s[2], vec3(2.0, 2.0, 0.0));
+    assert_eq!(polyline_smooth(&pts, 0), pts);
+}
@@ -2,6 +2,8 @@ use crate::*;
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Default, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
 pub struct Rect {
     pub xy: Vec2,
     pub wh: Vec2,
@@ -46,6 +48,7 @@ impl Rect {
         rectv(self.xy + vec2(i as f32, j as f32) * v, v)
     }
     /// returns grid rect i,j. widths and heights as weights of parent vect.
+    #[allow(clippy::needless_range_loop)]
     pub fn grid(&self, i: usize, j: usize, widths: &[f32], heights: &[f32]) -> Rect {
         let mut acc = vec2(0.0, 0.0);
         let sw = widths.iter().fold(0.0f32, |acc: f32, e| acc + e);
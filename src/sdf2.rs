@@ -0,0 +1,299 @@
+use crate::*;
+
+// primitives are centred on the origin unless they take points. formulas mostly from inigo quilez
+
+pub fn sdf2_circle(p: Vec2, r: f32) -> f32 { p.norm() - r }
+
+/// axis aligned box with half extents half
+pub fn sdf2_box(p: Vec2, half: Vec2) -> f32 {
+    let d = vec2(p.x.abs(), p.y.abs()) - half;
+    d.max(vec2(0.0, 0.0)).norm() + d.x.max(d.y).min(0.0)
+}
+
+/// box with corners rounded by r, still fitting within half
+pub fn sdf2_rounded_box(p: Vec2, half: Vec2, r: f32) -> f32 {
+    sdf2_box(p, half - r) - r
+}
+
+/// unsigned distance to the segment ab
+pub fn sdf2_segment(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let l2 = ba.dot(ba);
+    let h = if l2 == 0.0 { 0.0 } else { (pa.dot(ba) / l2).clamp(0.0, 1.0) };
+    (pa - ba * h).norm()
+}
+
+pub fn sdf2_capsule(p: Vec2, a: Vec2, b: Vec2, r: f32) -> f32 {
+    sdf2_segment(p, a, b) - r
+}
+
+/// triangle with either winding
+pub fn sdf2_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    let e = [b - a, c - b, a - c];
+    let v = [p - a, p - b, p - c];
+    let s = e[0].cross(e[2]).signum();
+    let mut d = (f32::INFINITY, f32::INFINITY);
+    for i in 0..3 {
+        let pq = v[i] - e[i] * (v[i].dot(e[i]) / e[i].dot(e[i])).clamp(0.0, 1.0);
+        d.0 = d.0.min(pq.dot(pq));
+        d.1 = d.1.min(-s * e[i].cross(v[i]));
+    }
+    -d.0.sqrt() * d.1.signum()
+}
+
+/// simple polygon (no self intersections) with either winding
+pub fn sdf2_polygon(p: Vec2, v: &[Vec2]) -> f32 {
+    let Some(&first) = v.first() else { return f32::INFINITY };
+    let mut d = (p - first).dot(p - first);
+    let mut s = 1.0;
+    let mut j = v.len() - 1;
+    for i in 0..v.len() {
+        let e = v[j] - v[i];
+        let w = p - v[i];
+        let l2 = e.dot(e);
+        let h = if l2 == 0.0 { 0.0 } else { (w.dot(e) / l2).clamp(0.0, 1.0) };
+        let b = w - e * h;
+        d = d.min(b.dot(b));
+        // crossing number, sign flips each time a ray to +x crosses an edge
+        let c = [p.y >= v[i].y, p.y < v[j].y, e.x * w.y > e.y * w.x];
+        if c.iter().all(|c| *c) || c.iter().all(|c| !*c) {
+            s = -s;
+        }
+        j = i;
+    }
+    s * d.sqrt()
+}
+
+/// arc of radius r and thickness th, symmetric about +y, spanning half_angle either side of it
+pub fn sdf2_arc(p: Vec2, half_angle: f32, r: f32, th: f32) -> f32 {
+    let sc = vec2(half_angle.sin(), half_angle.cos());
+    let p = vec2(p.x.abs(), p.y);
+    let d = if sc.y * p.x > sc.x * p.y { (p - sc * r).norm() } else { (p.norm() - r).abs() };
+    d - th
+}
+
+/// annulus of radius r and thickness th either side
+pub fn sdf2_ring(p: Vec2, r: f32, th: f32) -> f32 {
+    (p.norm() - r).abs() - th
+}
+
+/// n pointed star with outer radius r. m in [2, n] sets how sharp the points are, m = 2 is a regular polygon
+pub fn sdf2_star(p: Vec2, r: f32, n: u32, m: f32) -> f32 {
+    let an = core::f32::consts::PI / n as f32;
+    let en = core::f32::consts::PI / m;
+    let acs = vec2(an.cos(), an.sin());
+    let ecs = vec2(en.cos(), en.sin());
+    let bn = (p.x.atan2(p.y)).rem_euclid(2.0 * an) - an;
+    let p = vec2(bn.cos(), bn.sin().abs()) * p.norm();
+    let p = p - acs * r;
+    let p = p + ecs * (-p.dot(ecs)).clamp(0.0, r * acs.y / ecs.y);
+    p.norm() * p.x.signum()
+}
+
+pub fn sdf_union(a: f32, b: f32) -> f32 { a.min(b) }
+pub fn sdf_intersection(a: f32, b: f32) -> f32 { a.max(b) }
+/// a with b cut out of it
+pub fn sdf_subtraction(a: f32, b: f32) -> f32 { a.max(-b) }
+/// polynomial smooth min, k is roughly the size of the blend region
+pub fn sdf_smooth_union(a: f32, b: f32, k: f32) -> f32 {
+    if k <= 0.0 {
+        return a.min(b);
+    }
+    let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+    b + (a - b) * h - k * h * (1.0 - h)
+}
+pub fn sdf_smooth_intersection(a: f32, b: f32, k: f32) -> f32 { -sdf_smooth_union(-a, -b, k) }
+pub fn sdf_smooth_subtraction(a: f32, b: f32, k: f32) -> f32 { sdf_smooth_intersection(a, -b, k) }
+
+/// central difference gradient of any 2d distance function
+pub fn sdf2_gradient(f: impl Fn(Vec2) -> f32, p: Vec2) -> Vec2 {
+    let h = 1e-3;
+    vec2(
+        f(p + vec2(h, 0.0)) - f(p - vec2(h, 0.0)),
+        f(p + vec2(0.0, h)) - f(p - vec2(0.0, h)),
+    ) / (2.0 * h)
+}
+
+/// a 2d distance function built out of primitives, transforms and combinators.
+/// eg `Sdf2::circle(1.0).translate(vec2(1.0, 0.0)).smooth_union(Sdf2::rect(vec2(1.0, 0.5)), 0.2)`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sdf2 {
+    Circle(f32),
+    Box(Vec2),
+    RoundedBox(Vec2, f32),
+    Segment(Vec2, Vec2),
+    Capsule(Vec2, Vec2, f32),
+    Triangle(Vec2, Vec2, Vec2),
+    Polygon(Vec<Vec2>),
+    Arc(f32, f32, f32),
+    Ring(f32, f32),
+    Star(f32, u32, f32),
+    Translate(Box<Sdf2>, Vec2),
+    /// radians anticlockwise
+    Rotate(Box<Sdf2>, f32),
+    Scale(Box<Sdf2>, f32),
+    Union(Box<Sdf2>, Box<Sdf2>),
+    Intersection(Box<Sdf2>, Box<Sdf2>),
+    Subtraction(Box<Sdf2>, Box<Sdf2>),
+    SmoothUnion(Box<Sdf2>, Box<Sdf2>, f32),
+    SmoothIntersection(Box<Sdf2>, Box<Sdf2>, f32),
+    SmoothSubtraction(Box<Sdf2>, Box<Sdf2>, f32),
+    Round(Box<Sdf2>, f32),
+    Onion(Box<Sdf2>, f32),
+    /// infinite repetition with the given period, a zero period component doesnt repeat on that axis
+    Repeat(Box<Sdf2>, Vec2),
+}
+
+impl Sdf2 {
+    pub fn circle(r: f32) -> Self { Sdf2::Circle(r) }
+    pub fn rect(half: Vec2) -> Self { Sdf2::Box(half) }
+    pub fn rounded_rect(half: Vec2, r: f32) -> Self { Sdf2::RoundedBox(half, r) }
+    pub fn segment(a: Vec2, b: Vec2) -> Self { Sdf2::Segment(a, b) }
+    pub fn capsule(a: Vec2, b: Vec2, r: f32) -> Self { Sdf2::Capsule(a, b, r) }
+    pub fn triangle(a: Vec2, b: Vec2, c: Vec2) -> Self { Sdf2::Triangle(a, b, c) }
+    pub fn polygon(points: Vec<Vec2>) -> Self { Sdf2::Polygon(points) }
+    pub fn arc(half_angle: f32, r: f32, th: f32) -> Self { Sdf2::Arc(half_angle, r, th) }
+    pub fn ring(r: f32, th: f32) -> Self { Sdf2::Ring(r, th) }
+    pub fn star(r: f32, n: u32, m: f32) -> Self { Sdf2::Star(r, n, m) }
+
+    pub fn translate(self, v: Vec2) -> Self { Sdf2::Translate(Box::new(self), v) }
+    pub fn rotate(self, angle: f32) -> Self { Sdf2::Rotate(Box::new(self), angle) }
+    pub fn scale(self, s: f32) -> Self { Sdf2::Scale(Box::new(self), s) }
+    pub fn union(self, other: Sdf2) -> Self { Sdf2::Union(Box::new(self), Box::new(other)) }
+    pub fn intersection(self, other: Sdf2) -> Self { Sdf2::Intersection(Box::new(self), Box::new(other)) }
+    pub fn subtraction(self, other: Sdf2) -> Self { Sdf2::Subtraction(Box::new(self), Box::new(other)) }
+    pub fn smooth_union(self, other: Sdf2, k: f32) -> Self { Sdf2::SmoothUnion(Box::new(self), Box::new(other), k) }
+    pub fn smooth_intersection(self, other: Sdf2, k: f32) -> Self { Sdf2::SmoothIntersection(Box::new(self), Box::new(other), k) }
+    pub fn smooth_subtraction(self, other: Sdf2, k: f32) -> Self { Sdf2::SmoothSubtraction(Box::new(self), Box::new(other), k) }
+    pub fn round(self, r: f32) -> Self { Sdf2::Round(Box::new(self), r) }
+    pub fn onion(self, th: f32) -> Self { Sdf2::Onion(Box::new(self), th) }
+    pub fn repeat(self, period: Vec2) -> Self { Sdf2::Repeat(Box::new(self), period) }
+
+    pub fn eval(&self, p: Vec2) -> f32 {
+        match self {
+            Sdf2::Circle(r) => sdf2_circle(p, *r),
+            Sdf2::Box(half) => sdf2_box(p, *half),
+            Sdf2::RoundedBox(half, r) => sdf2_rounded_box(p, *half, *r),
+            Sdf2::Segment(a, b) => sdf2_segment(p, *a, *b),
+            Sdf2::Capsule(a, b, r) => sdf2_capsule(p, *a, *b, *r),
+            Sdf2::Triangle(a, b, c) => sdf2_triangle(p, *a, *b, *c),
+            Sdf2::Polygon(v) => sdf2_polygon(p, v),
+            Sdf2::Arc(a, r, th) => sdf2_arc(p, *a, *r, *th),
+            Sdf2::Ring(r, th) => sdf2_ring(p, *r, *th),
+            Sdf2::Star(r, n, m) => sdf2_star(p, *r, *n, *m),
+            Sdf2::Translate(s, v) => s.eval(p - *v),
+            Sdf2::Rotate(s, a) => {
+                let (sin, cos) = a.sin_cos();
+                s.eval(vec2(cos * p.x + sin * p.y, -sin * p.x + cos * p.y))
+            }
+            Sdf2::Scale(s, k) => s.eval(p / *k) * k,
+            Sdf2::Union(a, b) => sdf_union(a.eval(p), b.eval(p)),
+            Sdf2::Intersection(a, b) => sdf_intersection(a.eval(p), b.eval(p)),
+            Sdf2::Subtraction(a, b) => sdf_subtraction(a.eval(p), b.eval(p)),
+            Sdf2::SmoothUnion(a, b, k) => sdf_smooth_union(a.eval(p), b.eval(p), *k),
+            Sdf2::SmoothIntersection(a, b, k) => sdf_smooth_intersection(a.eval(p), b.eval(p), *k),
+            Sdf2::SmoothSubtraction(a, b, k) => sdf_smooth_subtraction(a.eval(p), b.eval(p), *k),
+            Sdf2::Round(s, r) => s.eval(p) - r,
+            Sdf2::Onion(s, th) => s.eval(p).abs() - th,
+            Sdf2::Repeat(s, period) => {
+                let wrap = |x: f32, k: f32| if k == 0.0 { x } else { x - k * (x / k).round() };
+                s.eval(vec2(wrap(p.x, period.x), wrap(p.y, period.y)))
+            }
+        }
+    }
+    pub fn gradient(&self, p: Vec2) -> Vec2 {
+        sdf2_gradient(|p| self.eval(p), p)
+    }
+}
+
+#[cfg(test)]
+fn approx(a: f32, b: f32) -> bool { (a - b).abs() < 1e-4 }
+
+#[test]
+fn test_sdf2_primitives() {
+    assert_eq!(sdf2_circle(vec2(3.0, 4.0), 1.0), 4.0);
+    let r = rect(-1.0, -2.0, 2.0, 4.0);
+    for p in [vec2(0.0, 0.0), vec2(3.0, 1.0), vec2(-4.0, 6.0), vec2(0.5, -1.9)] {
+        assert_eq!(sdf2_box(p, vec2(1.0, 2.0)), r.signed_distance(p));
+    }
+    assert!(approx(sdf2_rounded_box(vec2(2.0, 2.0), vec2(1.0, 1.0), 0.5), 2.0f32.sqrt() * 1.5 - 0.5));
+    assert_eq!(sdf2_segment(vec2(1.0, 1.0), vec2(0.0, 0.0), vec2(2.0, 0.0)), 1.0);
+    assert_eq!(sdf2_segment(vec2(5.0, 0.0), vec2(0.0, 0.0), vec2(2.0, 0.0)), 3.0);
+    assert_eq!(sdf2_capsule(vec2(1.0, 1.0), vec2(0.0, 0.0), vec2(2.0, 0.0), 0.25), 0.75);
+    assert_eq!(sdf2_ring(vec2(0.0, 0.0), 2.0, 0.5), 1.5);
+    assert_eq!(sdf2_ring(vec2(0.0, 2.25), 2.0, 0.5), -0.25);
+    // arc opening upward through 90 degrees total
+    assert!(approx(sdf2_arc(vec2(0.0, 3.0), core::f32::consts::FRAC_PI_4, 2.0, 0.1), 0.9));
+    let end = vec2(1.0, 1.0) * 2.0f32.sqrt();
+    assert!(approx(sdf2_arc(vec2(0.0, -2.0), core::f32::consts::FRAC_PI_4, 2.0, 0.1), (vec2(0.0, -2.0) - end).norm() - 0.1));
+}
+
+#[test]
+fn test_sdf2_triangle_polygon() {
+    let (a, b, c) = (vec2(0.0, 0.0), vec2(4.0, 0.0), vec2(0.0, 4.0));
+    for p in [vec2(1.0, 1.0), vec2(-1.0, -1.0), vec2(3.0, 3.0), vec2(2.0, -0.5), vec2(0.1, 3.0)] {
+        let t = sdf2_triangle(p, a, b, c);
+        assert!(approx(t, sdf2_triangle(p, a, c, b)));
+        assert!(approx(t, sdf2_polygon(p, &[a, b, c])));
+        assert!(approx(t, sdf2_polygon(p, &[c, b, a])));
+    }
+    assert_eq!(sdf2_triangle(vec2(1.0, 1.0), a, b, c), -1.0);
+    assert_eq!(sdf2_triangle(vec2(2.0, -0.5), a, b, c), 0.5);
+    // an L shape, concave
+    let l = [vec2(0.0, 0.0), vec2(2.0, 0.0), vec2(2.0, 1.0), vec2(1.0, 1.0), vec2(1.0, 2.0), vec2(0.0, 2.0)];
+    assert!(approx(sdf2_polygon(vec2(1.5, 1.5), &l), 0.5));
+    assert!(approx(sdf2_polygon(vec2(0.5, 0.5), &l), -0.5));
+}
+
+#[test]
+fn test_sdf2_star() {
+    // points are on the +y axis at radius r
+    assert!(approx(sdf2_star(vec2(0.0, 1.0), 1.0, 5, 3.0), 0.0));
+    assert!(approx(sdf2_star(vec2(0.0, 2.0), 1.0, 5, 3.0), 1.0));
+    assert!(sdf2_star(vec2(0.0, 0.0), 1.0, 5, 3.0) < 0.0);
+    // m = 2 is a regular polygon, so a square with n = 4 rotated 45 degrees
+    let sq = Sdf2::rect(vec2(1.0, 1.0)).rotate(core::f32::consts::FRAC_PI_4);
+    for p in [vec2(0.3, 0.1), vec2(2.0, 0.5), vec2(-1.0, 3.0)] {
+        assert!(approx(sdf2_star(p, 2.0f32.sqrt(), 4, 2.0), sq.eval(p)));
+    }
+}
+
+#[test]
+fn test_sdf2_ops() {
+    assert_eq!(sdf_union(1.0, 2.0), 1.0);
+    assert_eq!(sdf_intersection(1.0, 2.0), 2.0);
+    assert_eq!(sdf_subtraction(1.0, 2.0), 1.0);
+    assert_eq!(sdf_subtraction(-1.0, -2.0), 2.0);
+    // smooth versions agree far from the seam and undercut near it
+    assert_eq!(sdf_smooth_union(1.0, 5.0, 0.5), 1.0);
+    assert!(sdf_smooth_union(1.0, 1.0, 0.5) < 1.0);
+    assert!(sdf_smooth_intersection(1.0, 1.0, 0.5) > 1.0);
+    assert_eq!(sdf_smooth_subtraction(1.0, -5.0, 0.5), 5.0);
+}
+
+#[test]
+fn test_sdf2_tree() {
+    let s = Sdf2::circle(1.0).translate(vec2(2.0, 0.0)).union(Sdf2::circle(1.0).translate(vec2(-2.0, 0.0)));
+    assert_eq!(s.eval(vec2(2.0, 0.0)), -1.0);
+    assert_eq!(s.eval(vec2(-2.0, 2.0)), 1.0);
+    assert_eq!(s.eval(vec2(0.0, 0.0)), 1.0);
+    let g = s.gradient(vec2(2.0, 3.0));
+    assert!((g - vec2(0.0, 1.0)).norm() < 1e-3);
+
+    let s = Sdf2::rect(vec2(2.0, 1.0)).rotate(core::f32::consts::FRAC_PI_2);
+    assert!(approx(s.eval(vec2(0.0, 3.0)), 1.0));
+    assert!(approx(s.eval(vec2(3.0, 0.0)), 2.0));
+
+    let s = Sdf2::circle(1.0).scale(2.0).onion(0.1);
+    assert!(approx(s.eval(vec2(0.0, 0.0)), 1.9));
+    assert!(approx(s.eval(vec2(2.0, 0.0)), -0.1));
+
+    let s = Sdf2::circle(0.5).repeat(vec2(4.0, 0.0));
+    assert!(approx(s.eval(vec2(8.0, 0.0)), -0.5));
+    assert!(approx(s.eval(vec2(8.0, 4.0)), 3.5));
+
+    let s = Sdf2::rect(vec2(1.0, 1.0)).round(0.5).subtraction(Sdf2::circle(0.5));
+    assert!(approx(s.eval(vec2(0.0, 0.0)), 0.5));
+    assert!(approx(s.eval(vec2(0.0, 1.5)), 0.0));
+}
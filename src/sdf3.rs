@@ -0,0 +1,248 @@
+use crate::*;
+
+// 3d counterparts of sdf2. combine them with sdf_union etc from there
+
+fn abs3(p: Vec3) -> Vec3 { vec3(p.x.abs(), p.y.abs(), p.z.abs()) }
+
+pub fn sdf3_sphere(p: Vec3, r: f32) -> f32 { p.norm() - r }
+
+/// axis aligned box with half extents half
+pub fn sdf3_box(p: Vec3, half: Vec3) -> f32 {
+    let q = abs3(p) - half;
+    q.max(vec3(0.0, 0.0, 0.0)).norm() + q.x.max(q.y).max(q.z).min(0.0)
+}
+
+/// box with edges rounded by r, still fitting within half
+pub fn sdf3_rounded_box(p: Vec3, half: Vec3, r: f32) -> f32 {
+    sdf3_box(p, half - r) - r
+}
+
+/// torus in the xz plane, major radius r, tube radius th
+pub fn sdf3_torus(p: Vec3, r: f32, th: f32) -> f32 {
+    vec2(vec2(p.x, p.z).norm() - r, p.y).norm() - th
+}
+
+/// capped cylinder along y, radius r, from -h to h
+pub fn sdf3_cylinder(p: Vec3, r: f32, h: f32) -> f32 {
+    let d = vec2(vec2(p.x, p.z).norm() - r, p.y.abs() - h);
+    d.x.max(d.y).min(0.0) + d.max(vec2(0.0, 0.0)).norm()
+}
+
+pub fn sdf3_capsule(p: Vec3, a: Vec3, b: Vec3, r: f32) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let l2 = ba.dot(ba);
+    let h = if l2 == 0.0 { 0.0 } else { (pa.dot(ba) / l2).clamp(0.0, 1.0) };
+    (pa - ba * h).norm() - r
+}
+
+/// capped cone along y, radius r1 at -h and r2 at h. r2 = 0 for a pointy one
+pub fn sdf3_cone(p: Vec3, h: f32, r1: f32, r2: f32) -> f32 {
+    let q = vec2(vec2(p.x, p.z).norm(), p.y);
+    let k1 = vec2(r2, h);
+    let k2 = vec2(r2 - r1, 2.0 * h);
+    let ca = vec2(q.x - q.x.min(if q.y < 0.0 { r1 } else { r2 }), q.y.abs() - h);
+    let cb = q - k1 + k2 * ((k1 - q).dot(k2) / k2.dot(k2)).clamp(0.0, 1.0);
+    let s = if cb.x < 0.0 && ca.y < 0.0 { -1.0 } else { 1.0 };
+    s * ca.dot(ca).min(cb.dot(cb)).sqrt()
+}
+
+/// half space below the plane through n * h with unit normal n
+pub fn sdf3_plane(p: Vec3, n: Vec3, h: f32) -> f32 { p.dot(n) - h }
+
+/// surface normal of any 3d distance function, tetrahedron technique so its 4 evaluations rather than 6
+pub fn sdf3_normal(f: impl Fn(Vec3) -> f32, p: Vec3) -> Vec3 {
+    let h = 1e-3;
+    let k = [vec3(1.0, -1.0, -1.0), vec3(-1.0, -1.0, 1.0), vec3(-1.0, 1.0, -1.0), vec3(1.0, 1.0, 1.0)];
+    let n = k.iter().fold(vec3(0.0, 0.0, 0.0), |acc, k| acc + *k * f(p + *k * h));
+    n.unit().unwrap_or(vec3(0.0, 0.0, 0.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// distance along the ray
+    pub dist: f32,
+    pub point: Vec3,
+    pub steps: usize,
+}
+
+/// sphere tracing. None if the ray gets further than max_dist or runs out of steps.
+/// f has to be a true distance or an underestimate of one or it can step through things
+pub fn sdf3_raymarch(f: impl Fn(Vec3) -> f32, origin: Vec3, dir: Vec3, max_dist: f32, max_steps: usize) -> Option<RayHit> {
+    let dir = dir.unit()?;
+    let mut t = 0.0;
+    for steps in 1..=max_steps {
+        let point = origin + dir * t;
+        let d = f(point);
+        if d.abs() < 1e-4 * (1.0 + t) {
+            return Some(RayHit { dist: t, point, steps });
+        }
+        t += d;
+        if t > max_dist {
+            return None;
+        }
+    }
+    None
+}
+
+/// a 3d distance function built out of primitives, transforms and combinators, see Sdf2
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sdf3 {
+    Sphere(f32),
+    Box(Vec3),
+    RoundedBox(Vec3, f32),
+    Torus(f32, f32),
+    Cylinder(f32, f32),
+    Capsule(Vec3, Vec3, f32),
+    Cone(f32, f32, f32),
+    Plane(Vec3, f32),
+    Translate(Box<Sdf3>, Vec3),
+    /// unit axis and radians anticlockwise about it
+    Rotate(Box<Sdf3>, Vec3, f32),
+    Scale(Box<Sdf3>, f32),
+    /// reflect across each plane x = 0, y = 0, z = 0 whose component is nonzero
+    Mirror(Box<Sdf3>, Vec3),
+    /// infinite repetition with the given period, a zero period component doesnt repeat on that axis
+    Repeat(Box<Sdf3>, Vec3),
+    Union(Box<Sdf3>, Box<Sdf3>),
+    Intersection(Box<Sdf3>, Box<Sdf3>),
+    Subtraction(Box<Sdf3>, Box<Sdf3>),
+    SmoothUnion(Box<Sdf3>, Box<Sdf3>, f32),
+    SmoothIntersection(Box<Sdf3>, Box<Sdf3>, f32),
+    SmoothSubtraction(Box<Sdf3>, Box<Sdf3>, f32),
+    Round(Box<Sdf3>, f32),
+    Onion(Box<Sdf3>, f32),
+}
+
+impl Sdf3 {
+    pub fn sphere(r: f32) -> Self { Sdf3::Sphere(r) }
+    pub fn cuboid(half: Vec3) -> Self { Sdf3::Box(half) }
+    pub fn rounded_cuboid(half: Vec3, r: f32) -> Self { Sdf3::RoundedBox(half, r) }
+    pub fn torus(r: f32, th: f32) -> Self { Sdf3::Torus(r, th) }
+    pub fn cylinder(r: f32, h: f32) -> Self { Sdf3::Cylinder(r, h) }
+    pub fn capsule(a: Vec3, b: Vec3, r: f32) -> Self { Sdf3::Capsule(a, b, r) }
+    pub fn cone(h: f32, r1: f32, r2: f32) -> Self { Sdf3::Cone(h, r1, r2) }
+    pub fn plane(n: Vec3, h: f32) -> Self { Sdf3::Plane(n, h) }
+
+    pub fn translate(self, v: Vec3) -> Self { Sdf3::Translate(Box::new(self), v) }
+    pub fn rotate(self, axis: Vec3, angle: f32) -> Self { Sdf3::Rotate(Box::new(self), axis, angle) }
+    pub fn scale(self, s: f32) -> Self { Sdf3::Scale(Box::new(self), s) }
+    pub fn mirror(self, axes: Vec3) -> Self { Sdf3::Mirror(Box::new(self), axes) }
+    pub fn repeat(self, period: Vec3) -> Self { Sdf3::Repeat(Box::new(self), period) }
+    pub fn union(self, other: Sdf3) -> Self { Sdf3::Union(Box::new(self), Box::new(other)) }
+    pub fn intersection(self, other: Sdf3) -> Self { Sdf3::Intersection(Box::new(self), Box::new(other)) }
+    pub fn subtraction(self, other: Sdf3) -> Self { Sdf3::Subtraction(Box::new(self), Box::new(other)) }
+    pub fn smooth_union(self, other: Sdf3, k: f32) -> Self { Sdf3::SmoothUnion(Box::new(self), Box::new(other), k) }
+    pub fn smooth_intersection(self, other: Sdf3, k: f32) -> Self { Sdf3::SmoothIntersection(Box::new(self), Box::new(other), k) }
+    pub fn smooth_subtraction(self, other: Sdf3, k: f32) -> Self { Sdf3::SmoothSubtraction(Box::new(self), Box::new(other), k) }
+    pub fn round(self, r: f32) -> Self { Sdf3::Round(Box::new(self), r) }
+    pub fn onion(self, th: f32) -> Self { Sdf3::Onion(Box::new(self), th) }
+
+    pub fn eval(&self, p: Vec3) -> f32 {
+        match self {
+            Sdf3::Sphere(r) => sdf3_sphere(p, *r),
+            Sdf3::Box(half) => sdf3_box(p, *half),
+            Sdf3::RoundedBox(half, r) => sdf3_rounded_box(p, *half, *r),
+            Sdf3::Torus(r, th) => sdf3_torus(p, *r, *th),
+            Sdf3::Cylinder(r, h) => sdf3_cylinder(p, *r, *h),
+            Sdf3::Capsule(a, b, r) => sdf3_capsule(p, *a, *b, *r),
+            Sdf3::Cone(h, r1, r2) => sdf3_cone(p, *h, *r1, *r2),
+            Sdf3::Plane(n, h) => sdf3_plane(p, *n, *h),
+            Sdf3::Translate(s, v) => s.eval(p - *v),
+            Sdf3::Rotate(s, axis, a) => s.eval(p.rotate_axis(*axis, -a)),
+            Sdf3::Scale(s, k) => s.eval(p / *k) * k,
+            Sdf3::Mirror(s, axes) => {
+                let m = |x: f32, k: f32| if k != 0.0 { x.abs() } else { x };
+                s.eval(vec3(m(p.x, axes.x), m(p.y, axes.y), m(p.z, axes.z)))
+            }
+            Sdf3::Repeat(s, period) => {
+                let wrap = |x: f32, k: f32| if k == 0.0 { x } else { x - k * (x / k).round() };
+                s.eval(vec3(wrap(p.x, period.x), wrap(p.y, period.y), wrap(p.z, period.z)))
+            }
+            Sdf3::Union(a, b) => sdf_union(a.eval(p), b.eval(p)),
+            Sdf3::Intersection(a, b) => sdf_intersection(a.eval(p), b.eval(p)),
+            Sdf3::Subtraction(a, b) => sdf_subtraction(a.eval(p), b.eval(p)),
+            Sdf3::SmoothUnion(a, b, k) => sdf_smooth_union(a.eval(p), b.eval(p), *k),
+            Sdf3::SmoothIntersection(a, b, k) => sdf_smooth_intersection(a.eval(p), b.eval(p), *k),
+            Sdf3::SmoothSubtraction(a, b, k) => sdf_smooth_subtraction(a.eval(p), b.eval(p), *k),
+            Sdf3::Round(s, r) => s.eval(p) - r,
+            Sdf3::Onion(s, th) => s.eval(p).abs() - th,
+        }
+    }
+    pub fn normal(&self, p: Vec3) -> Vec3 {
+        sdf3_normal(|p| self.eval(p), p)
+    }
+    pub fn raymarch(&self, origin: Vec3, dir: Vec3, max_dist: f32, max_steps: usize) -> Option<RayHit> {
+        sdf3_raymarch(|p| self.eval(p), origin, dir, max_dist, max_steps)
+    }
+}
+
+#[cfg(test)]
+fn approx(a: f32, b: f32) -> bool { (a - b).abs() < 1e-4 }
+
+#[test]
+fn test_sdf3_primitives() {
+    assert_eq!(sdf3_sphere(vec3(0.0, 3.0, 4.0), 1.0), 4.0);
+    assert_eq!(sdf3_box(vec3(0.0, 0.0, 0.0), vec3(1.0, 2.0, 3.0)), -1.0);
+    assert_eq!(sdf3_box(vec3(2.0, 0.0, 0.0), vec3(1.0, 2.0, 3.0)), 1.0);
+    assert!(approx(sdf3_box(vec3(2.0, 3.0, 3.0), vec3(1.0, 2.0, 3.0)), 2.0f32.sqrt()));
+    assert!(approx(sdf3_rounded_box(vec3(2.0, 3.0, 3.0), vec3(1.0, 2.0, 3.0), 0.5), 4.75f32.sqrt() - 0.5));
+    assert_eq!(sdf3_torus(vec3(2.0, 0.0, 0.0), 2.0, 0.5), -0.5);
+    assert_eq!(sdf3_torus(vec3(0.0, 0.0, 0.0), 2.0, 0.5), 1.5);
+    assert_eq!(sdf3_cylinder(vec3(0.0, 3.0, 0.0), 1.0, 2.0), 1.0);
+    assert_eq!(sdf3_cylinder(vec3(3.0, 0.0, 0.0), 1.0, 2.0), 2.0);
+    assert_eq!(sdf3_cylinder(vec3(0.0, 0.0, 0.0), 1.0, 2.0), -1.0);
+    assert_eq!(sdf3_capsule(vec3(0.0, 5.0, 0.0), vec3(0.0, 0.0, 0.0), vec3(0.0, 2.0, 0.0), 1.0), 2.0);
+    assert_eq!(sdf3_plane(vec3(1.0, 5.0, 1.0), vec3(0.0, 1.0, 0.0), 2.0), 3.0);
+    // cone of height 2 (h = 1), base radius 1: tip at y = 1, base at y = -1
+    assert!(approx(sdf3_cone(vec3(0.0, 2.0, 0.0), 1.0, 1.0, 0.0), 1.0));
+    assert!(approx(sdf3_cone(vec3(0.0, -3.0, 0.0), 1.0, 1.0, 0.0), 2.0));
+    assert!(sdf3_cone(vec3(0.0, 0.0, 0.0), 1.0, 1.0, 0.0) < 0.0);
+    // with equal radii its a cylinder
+    assert!(approx(sdf3_cone(vec3(3.0, 0.5, 0.0), 2.0, 1.0, 1.0), sdf3_cylinder(vec3(3.0, 0.5, 0.0), 1.0, 2.0)));
+}
+
+#[test]
+fn test_sdf3_tree() {
+    let s = Sdf3::cylinder(0.5, 2.0).rotate(vec3(0.0, 0.0, 1.0), core::f32::consts::FRAC_PI_2);
+    // now lies along x
+    assert!(approx(s.eval(vec3(3.0, 0.0, 0.0)), 1.0));
+    assert!(approx(s.eval(vec3(0.0, 1.5, 0.0)), 1.0));
+
+    let s = Sdf3::sphere(1.0).translate(vec3(3.0, 0.0, 0.0)).mirror(vec3(1.0, 0.0, 0.0));
+    assert_eq!(s.eval(vec3(-3.0, 0.0, 0.0)), -1.0);
+    assert_eq!(s.eval(vec3(3.0, 0.0, 0.0)), -1.0);
+
+    let s = Sdf3::sphere(1.0).repeat(vec3(5.0, 0.0, 5.0)).scale(2.0);
+    assert!(approx(s.eval(vec3(10.0, 0.0, -10.0)), -2.0));
+    assert!(approx(s.eval(vec3(10.0, 4.0, -10.0)), 2.0));
+
+    let s = Sdf3::cuboid(vec3(1.0, 1.0, 1.0)).subtraction(Sdf3::sphere(1.2)).onion(0.1).union(Sdf3::torus(3.0, 0.2));
+    assert!(approx(s.eval(vec3(0.0, 0.0, 0.0)), 1.1));
+    assert!(approx(s.eval(vec3(3.0, 0.0, 0.0)), -0.2));
+}
+
+#[test]
+fn test_sdf3_normal() {
+    let s = Sdf3::sphere(1.0).translate(vec3(1.0, 1.0, 1.0));
+    let n = s.normal(vec3(1.0, 2.0, 1.0));
+    assert!((n - vec3(0.0, 1.0, 0.0)).norm() < 1e-3);
+    let n = sdf3_normal(|p| sdf3_box(p, vec3(1.0, 1.0, 1.0)), vec3(0.2, 0.1, 1.0));
+    assert!((n - vec3(0.0, 0.0, 1.0)).norm() < 1e-3);
+}
+
+#[test]
+fn test_sdf3_raymarch() {
+    let s = Sdf3::sphere(1.0).union(Sdf3::plane(vec3(0.0, 1.0, 0.0), -2.0));
+    let hit = s.raymarch(vec3(0.0, 0.0, -5.0), vec3(0.0, 0.0, 1.0), 100.0, 64).unwrap();
+    assert!(approx(hit.dist, 4.0));
+    assert!((hit.point - vec3(0.0, 0.0, -1.0)).norm() < 1e-3);
+    assert!(hit.steps > 0 && hit.steps < 64);
+    // looking down misses the sphere and hits the floor
+    let hit = s.raymarch(vec3(5.0, 0.0, 0.0), vec3(0.0, -2.0, 0.0), 100.0, 64).unwrap();
+    assert!(approx(hit.point.y, -2.0));
+    // looking up misses everything
+    assert_eq!(s.raymarch(vec3(5.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), 100.0, 64), None);
+    // too few steps to get there
+    assert_eq!(s.raymarch(vec3(0.0, 0.0, -50.0), vec3(0.01, 0.0, 1.0), 100.0, 1), None);
+}
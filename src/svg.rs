@@ -0,0 +1,405 @@
+use crate::*;
+use core::f32::consts::PI;
+use core::fmt::Write;
+
+/// one M...Z run of an svg path. arcs come out as cubics
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SubPath {
+    pub curves: Vec<Curve<Vec2>>,
+    pub closed: bool,
+}
+
+impl SubPath {
+    /// points along the subpath to within tol. a closed subpath doesnt repeat its first point
+    pub fn to_polyline(&self, tol: f32) -> Vec<Vec2> {
+        let mut out = Vec::new();
+        if let Some(c) = self.curves.first() {
+            out.push(c.start());
+        }
+        for c in self.curves.iter() {
+            c.flatten_into(tol, &mut out);
+        }
+        if self.closed && out.len() > 1 && out.first() == out.last() {
+            out.pop();
+        }
+        out
+    }
+    pub fn to_path(&self) -> Path<Vec2> {
+        Path::new(self.curves.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgError {
+    /// byte offset into the d string
+    pub pos: usize,
+    pub msg: String,
+}
+impl core::fmt::Display for SvgError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "svg path error at {}: {}", self.pos, self.msg)
+    }
+}
+impl core::error::Error for SvgError {}
+
+struct Lexer<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn err<T>(&self, msg: &str) -> Result<T, SvgError> {
+        Err(SvgError { pos: self.pos, msg: msg.to_string() })
+    }
+    fn skip_separators(&mut self) {
+        while self.pos < self.s.len() && (self.s[self.pos].is_ascii_whitespace() || self.s[self.pos] == b',') {
+            self.pos += 1;
+        }
+    }
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_separators();
+        self.s.get(self.pos).copied()
+    }
+    fn at_number(&mut self) -> bool {
+        matches!(self.peek(), Some(b'0'..=b'9' | b'.' | b'-' | b'+'))
+    }
+    fn number(&mut self) -> Result<f32, SvgError> {
+        if !self.at_number() {
+            return self.err("expected number");
+        }
+        let start = self.pos;
+        let mut i = self.pos;
+        let digits = |i: &mut usize, s: &[u8]| while *i < s.len() && s[*i].is_ascii_digit() { *i += 1 };
+        if matches!(self.s[i], b'-' | b'+') { i += 1; }
+        digits(&mut i, self.s);
+        if i < self.s.len() && self.s[i] == b'.' {
+            i += 1;
+            digits(&mut i, self.s);
+        }
+        // only take an exponent if its followed by digits, "1e" is a number then a bad command
+        if i < self.s.len() && matches!(self.s[i], b'e' | b'E') {
+            let mut j = i + 1;
+            if j < self.s.len() && matches!(self.s[j], b'-' | b'+') { j += 1; }
+            if j < self.s.len() && self.s[j].is_ascii_digit() {
+                i = j;
+                digits(&mut i, self.s);
+            }
+        }
+        let text = core::str::from_utf8(&self.s[start..i]).unwrap();
+        match text.parse::<f32>() {
+            Ok(x) => { self.pos = i; Ok(x) }
+            Err(_) => self.err("malformed number"),
+        }
+    }
+    fn point(&mut self) -> Result<Vec2, SvgError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(vec2(x, y))
+    }
+    /// arc flags are a single 0 or 1 and dont need separators: "a1 1 0 0110 10" is valid
+    fn flag(&mut self) -> Result<bool, SvgError> {
+        match self.peek() {
+            Some(b'0') => { self.pos += 1; Ok(false) }
+            Some(b'1') => { self.pos += 1; Ok(true) }
+            _ => self.err("expected flag"),
+        }
+    }
+}
+
+/// parse the d attribute of an svg path element
+pub fn svg_parse_path(d: &str) -> Result<Vec<SubPath>, SvgError> {
+    let mut lex = Lexer { s: d.as_bytes(), pos: 0 };
+    let mut out = Vec::new();
+    let mut sub = SubPath::default();
+    let mut cur = vec2(0.0, 0.0);
+    let mut start = cur;
+    let mut cmd: Option<u8> = None;
+    // last control point, for reflecting in S and T
+    let mut last_cubic_ctrl: Option<Vec2> = None;
+    let mut last_quad_ctrl: Option<Vec2> = None;
+
+    while let Some(c) = lex.peek() {
+        let c = if c.is_ascii_alphabetic() {
+            lex.pos += 1;
+            c
+        } else {
+            match cmd {
+                None => return lex.err("expected command"),
+                Some(b'M') => b'L',
+                Some(b'm') => b'l',
+                Some(b'Z' | b'z') => return lex.err("expected command after closepath"),
+                Some(c) => c,
+            }
+        };
+        cmd = Some(c);
+        let rel = c.is_ascii_lowercase();
+        let origin = if rel { cur } else { vec2(0.0, 0.0) };
+        let mut cubic_ctrl = None;
+        let mut quad_ctrl = None;
+        match c.to_ascii_uppercase() {
+            b'M' => {
+                let p = origin + lex.point()?;
+                if !sub.curves.is_empty() {
+                    out.push(core::mem::take(&mut sub));
+                }
+                cur = p;
+                start = p;
+            }
+            b'L' => {
+                let p = origin + lex.point()?;
+                sub.curves.push(Curve::Line(cur, p));
+                cur = p;
+            }
+            b'H' => {
+                let p = vec2(origin.x + lex.number()?, cur.y);
+                sub.curves.push(Curve::Line(cur, p));
+                cur = p;
+            }
+            b'V' => {
+                let p = vec2(cur.x, origin.y + lex.number()?);
+                sub.curves.push(Curve::Line(cur, p));
+                cur = p;
+            }
+            b'C' => {
+                let c1 = origin + lex.point()?;
+                let c2 = origin + lex.point()?;
+                let p = origin + lex.point()?;
+                sub.curves.push(Curve::Cubic(cur, c1, c2, p));
+                cubic_ctrl = Some(c2);
+                cur = p;
+            }
+            b'S' => {
+                let c1 = last_cubic_ctrl.map(|c| cur * 2.0 - c).unwrap_or(cur);
+                let c2 = origin + lex.point()?;
+                let p = origin + lex.point()?;
+                sub.curves.push(Curve::Cubic(cur, c1, c2, p));
+                cubic_ctrl = Some(c2);
+                cur = p;
+            }
+            b'Q' => {
+                let c1 = origin + lex.point()?;
+                let p = origin + lex.point()?;
+                sub.curves.push(Curve::Quadratic(cur, c1, p));
+                quad_ctrl = Some(c1);
+                cur = p;
+            }
+            b'T' => {
+                let c1 = last_quad_ctrl.map(|c| cur * 2.0 - c).unwrap_or(cur);
+                let p = origin + lex.point()?;
+                sub.curves.push(Curve::Quadratic(cur, c1, p));
+                quad_ctrl = Some(c1);
+                cur = p;
+            }
+            b'A' => {
+                let rx = lex.number()?;
+                let ry = lex.number()?;
+                let phi = lex.number()?;
+                let large = lex.flag()?;
+                let sweep = lex.flag()?;
+                let p = origin + lex.point()?;
+                arc_to_cubics(cur, vec2(rx, ry), phi, large, sweep, p, &mut sub.curves);
+                cur = p;
+            }
+            b'Z' => {
+                if cur != start {
+                    sub.curves.push(Curve::Line(cur, start));
+                }
+                sub.closed = true;
+                out.push(core::mem::take(&mut sub));
+                cur = start;
+            }
+            _ => {
+                lex.pos -= 1;
+                return lex.err("unknown command");
+            }
+        }
+        last_cubic_ctrl = cubic_ctrl;
+        last_quad_ctrl = quad_ctrl;
+    }
+    if !sub.curves.is_empty() {
+        out.push(sub);
+    }
+    Ok(out)
+}
+
+/// svg elliptical arc (endpoint parameterization, phi in degrees) as cubics of at most 90 degrees each
+fn arc_to_cubics(p0: Vec2, r: Vec2, phi: f32, large: bool, sweep: bool, p1: Vec2, out: &mut Vec<Curve<Vec2>>) {
+    if p0 == p1 {
+        return;
+    }
+    let (mut rx, mut ry) = (r.x.abs(), r.y.abs());
+    if rx == 0.0 || ry == 0.0 {
+        out.push(Curve::Line(p0, p1));
+        return;
+    }
+    // svg spec appendix B.2.4
+    let (s, c) = phi.to_radians().sin_cos();
+    let h = (p0 - p1) / 2.0;
+    let x1 = c * h.x + s * h.y;
+    let y1 = -s * h.x + c * h.y;
+    let lambda = x1*x1 / (rx*rx) + y1*y1 / (ry*ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let num = rx*rx*ry*ry - rx*rx*y1*y1 - ry*ry*x1*x1;
+    let den = rx*rx*y1*y1 + ry*ry*x1*x1;
+    let k = (num / den).max(0.0).sqrt() * if large == sweep { -1.0 } else { 1.0 };
+    let ccx = k * rx * y1 / ry;
+    let ccy = -k * ry * x1 / rx;
+    let m = (p0 + p1) / 2.0;
+    let centre = vec2(c * ccx - s * ccy + m.x, s * ccx + c * ccy + m.y);
+    let angle = |u: Vec2, v: Vec2| u.cross(v).atan2(u.dot(v));
+    let u = vec2((x1 - ccx) / rx, (y1 - ccy) / ry);
+    let v = vec2((-x1 - ccx) / rx, (-y1 - ccy) / ry);
+    let theta = angle(vec2(1.0, 0.0), u);
+    let mut dtheta = angle(u, v);
+    if !sweep && dtheta > 0.0 { dtheta -= 2.0 * PI; }
+    if sweep && dtheta < 0.0 { dtheta += 2.0 * PI; }
+
+    let n = (dtheta.abs() / (PI / 2.0) - 1e-4).ceil().max(1.0) as usize;
+    let delta = dtheta / n as f32;
+    let kappa = 4.0 / 3.0 * (delta / 4.0).tan();
+    // unit circle point to ellipse point
+    let map = |p: Vec2| centre + vec2(c * rx * p.x - s * ry * p.y, s * rx * p.x + c * ry * p.y);
+    let mut a = p0;
+    for i in 0..n {
+        let t0 = theta + delta * i as f32;
+        let t1 = t0 + delta;
+        let (s0, c0) = t0.sin_cos();
+        let (s1, c1) = t1.sin_cos();
+        let b = if i == n - 1 { p1 } else { map(vec2(c1, s1)) };
+        let q1 = map(vec2(c0 - kappa * s0, s0 + kappa * c0));
+        let q2 = map(vec2(c1 + kappa * s1, s1 - kappa * c1));
+        out.push(Curve::Cubic(a, q1, q2, b));
+        a = b;
+    }
+}
+
+/// d string for an open polyline
+pub fn svg_polyline(points: &[Vec2]) -> String {
+    let mut s = String::new();
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 { s.push(' '); }
+        write!(s, "{} {} {}", if i == 0 { 'M' } else { 'L' }, p.x, p.y).unwrap();
+    }
+    s
+}
+
+/// d string for a closed polygon
+pub fn svg_polygon(points: &[Vec2]) -> String {
+    let mut s = svg_polyline(points);
+    if !points.is_empty() {
+        s.push_str(" Z");
+    }
+    s
+}
+
+/// d string for a rect
+pub fn svg_rect(r: &Rect) -> String {
+    svg_polygon(&[r.tl(), r.tr(), r.br(), r.bl()])
+}
+
+/// d string for parsed subpaths, curves kept as curves
+pub fn svg_subpaths(subpaths: &[SubPath]) -> String {
+    let mut s = String::new();
+    for sub in subpaths {
+        let Some(first) = sub.curves.first() else { continue };
+        if !s.is_empty() { s.push(' '); }
+        let p = first.start();
+        write!(s, "M {} {}", p.x, p.y).unwrap();
+        let mut cur = p;
+        for c in sub.curves.iter() {
+            if c.start() != cur {
+                write!(s, " M {} {}", c.start().x, c.start().y).unwrap();
+            }
+            match *c {
+                Curve::Line(_, b) => write!(s, " L {} {}", b.x, b.y),
+                Curve::Quadratic(_, b, c) => write!(s, " Q {} {} {} {}", b.x, b.y, c.x, c.y),
+                Curve::Cubic(_, b, c, d) => write!(s, " C {} {} {} {} {} {}", b.x, b.y, c.x, c.y, d.x, d.y),
+            }.unwrap();
+            cur = c.end();
+        }
+        if sub.closed { s.push_str(" Z"); }
+    }
+    s
+}
+
+/// minimal standalone svg document with one stroked path per d string, for eyeballing geometry
+pub fn svg_document(view_box: Rect, paths: &[String]) -> String {
+    let mut s = String::new();
+    writeln!(s, r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#, view_box.xy.x, view_box.xy.y, view_box.wh.x, view_box.wh.y).unwrap();
+    for d in paths {
+        writeln!(s, r#"  <path d="{}" fill="none" stroke="black" vector-effect="non-scaling-stroke"/>"#, d).unwrap();
+    }
+    s.push_str("</svg>\n");
+    s
+}
+
+#[test]
+fn test_svg_parse_lines() {
+    let p = svg_parse_path("M10,10 L20 10 h5 v-5 l-1-1 20 20 H0 V0 z m1 1 2 2").unwrap();
+    assert_eq!(p.len(), 2);
+    assert!(p[0].closed);
+    let pts = p[0].to_polyline(0.1);
+    assert_eq!(pts, vec![
+        vec2(10.0, 10.0), vec2(20.0, 10.0), vec2(25.0, 10.0), vec2(25.0, 5.0),
+        vec2(24.0, 4.0), vec2(44.0, 24.0), vec2(0.0, 24.0), vec2(0.0, 0.0),
+    ]);
+    // after z the current point is back at the start of the subpath
+    assert_eq!(p[1].curves, vec![Curve::Line(vec2(11.0, 11.0), vec2(13.0, 13.0))]);
+    assert!(!p[1].closed);
+}
+
+#[test]
+fn test_svg_parse_smooth_curves() {
+    let p = svg_parse_path("M0 0 C 0 1 1 1 1 0 S 2 -1 2 0 Q 3 1 4 0 t 2 0").unwrap();
+    let c = &p[0].curves;
+    assert_eq!(c[1], Curve::Cubic(vec2(1.0, 0.0), vec2(1.0, -1.0), vec2(2.0, -1.0), vec2(2.0, 0.0)));
+    assert_eq!(c[3], Curve::Quadratic(vec2(4.0, 0.0), vec2(5.0, -1.0), vec2(6.0, 0.0)));
+}
+
+#[test]
+fn test_svg_parse_arc() {
+    // half circle radius 1 from (0,0) to (2,0), flags packed without separators
+    let p = svg_parse_path("M0 0a1 1 0 012 0").unwrap();
+    let c = &p[0].curves;
+    assert_eq!(c.len(), 2);
+    assert_eq!(c[1].end(), vec2(2.0, 0.0));
+    for pt in p[0].to_polyline(0.001) {
+        assert!((pt.dist(vec2(1.0, 0.0)) - 1.0).abs() < 1e-3);
+    }
+    // sweep flag 1 is the positive angle direction, from +x towards +y
+    assert!((c[0].end() - vec2(1.0, -1.0)).norm() < 1e-5);
+    // radii too small get scaled up to fit
+    let p = svg_parse_path("M0 0 A 0.1 0.1 0 0 0 2 0").unwrap();
+    assert!((p[0].curves[0].end() - vec2(1.0, 1.0)).norm() < 1e-5);
+}
+
+#[test]
+fn test_svg_parse_numbers() {
+    let p = svg_parse_path("M.5.5L1e1-2.5E-1").unwrap();
+    assert_eq!(p[0].curves[0], Curve::Line(vec2(0.5, 0.5), vec2(10.0, -0.25)));
+}
+
+#[test]
+fn test_svg_parse_errors() {
+    assert_eq!(svg_parse_path("10 10").unwrap_err().pos, 0);
+    assert_eq!(svg_parse_path("M 0 0 L 1").unwrap_err().pos, 9);
+    assert_eq!(svg_parse_path("M 0 0 X 1 1").unwrap_err().pos, 6);
+    assert!(svg_parse_path("M 0 0 Z 1 1").is_err());
+    assert_eq!(svg_parse_path("").unwrap(), vec![]);
+}
+
+#[test]
+fn test_svg_round_trip() {
+    let r = rect(1.0, 2.0, 3.0, 4.0);
+    assert_eq!(svg_rect(&r), "M 1 2 L 4 2 L 4 6 L 1 6 Z");
+    let p = svg_parse_path(&svg_rect(&r)).unwrap();
+    assert_eq!(p[0].to_polyline(0.1), vec![r.tl(), r.tr(), r.br(), r.bl()]);
+    let src = svg_parse_path("M0 0 Q 1 1 2 0 C 3 0 3 1 2.5 2 Z").unwrap();
+    assert_eq!(svg_parse_path(&svg_subpaths(&src)).unwrap(), src);
+    let doc = svg_document(r, &[svg_polyline(&[vec2(0.0, 0.0), vec2(1.0, 1.0)])]);
+    assert!(doc.contains(r#"viewBox="1 2 3 4""#));
+    assert!(doc.contains(r#"d="M 0 0 L 1 1""#));
+}
@@ -0,0 +1,109 @@
+use crate::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseVecError {
+    /// byte offset into the input
+    pub pos: usize,
+    pub msg: String,
+}
+impl core::fmt::Display for ParseVecError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "can't parse vector at {}: {}", self.pos, self.msg)
+    }
+}
+impl core::error::Error for ParseVecError {}
+
+/// "(a, b, ...)" with the formatter's width, precision, fill and sign applied to every component
+pub(crate) fn fmt_components(f: &mut core::fmt::Formatter, cs: &[f32]) -> core::fmt::Result {
+    write!(f, "(")?;
+    for (i, c) in cs.iter().enumerate() {
+        if i > 0 { write!(f, ", ")?; }
+        core::fmt::Display::fmt(c, f)?;
+    }
+    write!(f, ")")
+}
+
+/// exactly N numbers separated by commas and/or whitespace, optionally wrapped in () or []
+pub(crate) fn parse_components<const N: usize>(s: &str) -> Result<[f32; N], ParseVecError> {
+    let err = |pos: usize, msg: String| Err(ParseVecError { pos, msg });
+    let b = s.as_bytes();
+    let (mut start, mut end) = (0, b.len());
+    while start < end && b[start].is_ascii_whitespace() { start += 1; }
+    while end > start && b[end - 1].is_ascii_whitespace() { end -= 1; }
+    if start == end { return err(start, "empty input".to_string()); }
+    let close = match b[start] { b'(' => Some(b')'), b'[' => Some(b']'), _ => None };
+    if let Some(c) = close {
+        if end - start < 2 || b[end - 1] != c { return err(end, format!("expected closing '{}'", c as char)); }
+        start += 1;
+        end -= 1;
+    }
+
+    let mut out = [0.0; N];
+    let mut n = 0;
+    let mut i = start;
+    loop {
+        while i < end && b[i].is_ascii_whitespace() { i += 1; }
+        let tok = i;
+        while i < end && !b[i].is_ascii_whitespace() && b[i] != b',' { i += 1; }
+        if tok == i { return err(tok, "expected a number".to_string()); }
+        if n == N { return err(tok, format!("too many components, expected {}", N)); }
+        out[n] = match s[tok..i].parse() {
+            Ok(v) => v,
+            Err(_) => return err(tok, format!("invalid number {:?}", &s[tok..i])),
+        };
+        n += 1;
+        while i < end && b[i].is_ascii_whitespace() { i += 1; }
+        if i == end { break; }
+        if b[i] == b',' { i += 1; }
+    }
+    if n < N { return err(end, format!("expected {} components, found {}", N, n)); }
+    Ok(out)
+}
+
+/// "(x, y, w, h)"
+impl core::fmt::Display for Rect {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt_components(f, &[self.xy.x, self.xy.y, self.wh.x, self.wh.y])
+    }
+}
+/// x, y, w, h in any of the forms vectors take
+impl core::str::FromStr for Rect {
+    type Err = ParseVecError;
+    fn from_str(s: &str) -> Result<Rect, ParseVecError> { parse_components(s).map(|[x, y, w, h]| rect(x, y, w, h)) }
+}
+
+#[test]
+fn test_parse_forms() {
+    for s in ["1,2", "1, 2", "(1, 2)", "[1 2]", "  ( 1 ,2 ) ", "1 2", "[1.0,\t2e0]"] {
+        assert_eq!(s.parse::<Vec2>(), Ok(vec2(1.0, 2.0)), "{s}");
+    }
+    assert_eq!("-1.5, 0, 3".parse::<Vec3>(), Ok(vec3(-1.5, 0.0, 3.0)));
+    assert_eq!("[0.25 0.5 1 1]".parse::<Vec4>(), Ok(vec4(0.25, 0.5, 1.0, 1.0)));
+    assert_eq!("(10, 20, 30, 40)".parse::<Rect>(), Ok(rect(10.0, 20.0, 30.0, 40.0)));
+}
+
+#[test]
+fn test_parse_errors() {
+    let e = |s: &str| s.parse::<Vec2>().unwrap_err();
+    assert_eq!(e(""), ParseVecError { pos: 0, msg: "empty input".to_string() });
+    assert_eq!(e("(1, 2"), ParseVecError { pos: 5, msg: "expected closing ')'".to_string() });
+    assert_eq!(e("[1, 2)"), ParseVecError { pos: 6, msg: "expected closing ']'".to_string() });
+    assert_eq!(e("1, x"), ParseVecError { pos: 3, msg: "invalid number \"x\"".to_string() });
+    assert_eq!(e("1,,2"), ParseVecError { pos: 2, msg: "expected a number".to_string() });
+    assert_eq!(e("1, 2,"), ParseVecError { pos: 5, msg: "expected a number".to_string() });
+    assert_eq!(e("(1)"), ParseVecError { pos: 2, msg: "expected 2 components, found 1".to_string() });
+    assert_eq!(e("1 2 3"), ParseVecError { pos: 4, msg: "too many components, expected 2".to_string() });
+    assert_eq!(e("1, x").to_string(), "can't parse vector at 3: invalid number \"x\"");
+}
+
+#[test]
+fn test_display_round_trip() {
+    assert_eq!(format!("{:6.2}", vec2(1.0, -2.5)), "(  1.00,  -2.50)");
+    assert_eq!(format!("{:+}", vec3(1.0, 0.0, -3.0)), "(+1, +0, -3)");
+    assert_eq!(format!("{:<5}|", vec2(1.0, 2.0)), "(1    , 2    )|");
+    assert_eq!(format!("{:.1}", rect(0.0, 1.0, 2.5, 3.25)), "(0.0, 1.0, 2.5, 3.2)");
+    let v = vec4(0.1, -1e-7, 12345.678, f32::MAX);
+    assert_eq!(v.to_string().parse::<Vec4>(), Ok(v));
+    let r = rect(-1.0, 0.5, 3.0, 1e9);
+    assert_eq!(r.to_string().parse::<Rect>(), Ok(r));
+}
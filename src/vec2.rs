@@ -1,21 +1,30 @@
-use serde::{Serialize, Deserialize};
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize, Default)]
+#[cfg(all(feature = "libm", not(any(feature = "std", test))))]
+use crate::math::FloatMath;
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
 }
 pub const fn vec2(x: f32, y: f32) -> Vec2 { Vec2 { x, y } }
+impl_vector!(Vec2 { x, y });
 impl Vec2 {
-    pub fn norm(&self) -> f32 { self.dot(*self).sqrt() }
-    pub fn dist(&self, other: Self) -> f32 { (*self - other).norm() }
-    pub fn lerp(&self, other: Self, t: f32) -> Self { *self * t + other * (1.0 - t) }
-    pub fn unit(&self) -> Option<Self> { let n = self.norm(); if n == 0.0 {None} else {Some(*self/n)}}
-    pub fn dot(&self, other: Self) -> f32 { self.x*other.x + self.y*other.y }
-    pub fn min(&self, other: Self) -> Self { vec2(self.x.min(other.x), self.y.min(other.y)) }
-    pub fn max(&self, other: Self) -> Self { vec2(self.x.max(other.x), self.y.max(other.y)) }
-    pub fn floor(&self) -> Self { vec2(self.x.floor(), self.y.floor()) }
-    pub fn ceil(&self) -> Self { vec2(self.x.ceil(), self.y.ceil()) }
     pub fn cross(&self, other: Vec2) -> f32 { self.x * other.y - other.x * self.y }
+    /// rotated 90 degrees anticlockwise
+    pub fn perp(&self) -> Vec2 { vec2(-self.y, self.x) }
+    /// rotated angle radians anticlockwise
+    pub fn rotate(&self, angle: f32) -> Vec2 {
+        let (s, c) = angle.sin_cos();
+        vec2(self.x * c - self.y * s, self.x * s + self.y * c)
+    }
+    /// unit vector at angle radians anticlockwise from +x
+    pub fn from_angle(angle: f32) -> Vec2 { vec2(angle.cos(), angle.sin()) }
+    /// angle from +x in (-pi, pi]
+    pub fn to_angle(&self) -> f32 { self.y.atan2(self.x) }
+    /// angle to rotate self by to point along other, in (-pi, pi]
+    pub fn signed_angle(&self, other: Vec2) -> f32 { self.cross(other).atan2(self.dot(other)) }
     pub fn mul_complex(&self, other: Vec2) -> Vec2 {
         let a = self.x;
         let b = self.y;
@@ -34,130 +43,33 @@ impl Vec2 {
         vec2(a*c + b*d, b*c - a*d) / denom
     }
 }
-impl std::fmt::Display for Vec2 {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
-}
-impl std::ops::Neg for Vec2 {
-    type Output = Vec2;
-    fn neg(self) -> Vec2 {
-        vec2(-self.x,-self.y)
-    }
-}
-impl std::ops::Add<Vec2> for Vec2 {
-    type Output = Vec2;
-    fn add(self, rhs: Vec2) -> Vec2 {
-        vec2(self.x+rhs.x, self.y+rhs.y)
-    }
-}
-impl std::ops::Add<Vec2> for f32 {
-    type Output = Vec2;
-    fn add(self, rhs: Vec2) -> Vec2 {
-        vec2(self + rhs.x, self + rhs.y)
-    }
-}
-impl std::ops::Add<f32> for Vec2 {
-    type Output = Vec2;
-    fn add(self, rhs: f32) -> Vec2 {
-        vec2(self.x + rhs, self.y + rhs)
-    }
-}
-impl std::ops::Sub<Vec2> for Vec2 {
-    type Output = Vec2;
-    fn sub(self, rhs: Vec2) -> Vec2 {
-        vec2(self.x - rhs.x, self.y - rhs.y)
-    }
-}
-impl std::ops::Sub<f32> for Vec2 {
-    type Output = Vec2;
-    fn sub(self, rhs: f32) -> Self::Output {
-        vec2(self.x - rhs, self.y - rhs)
-    }
-}
-impl std::ops::Sub<Vec2> for f32 {
-    type Output = Vec2;
-    fn sub(self, rhs: Vec2) -> Self::Output {
-        vec2(self - rhs.x, self - rhs.y)
-    }
-}
-impl std::ops::Mul<f32> for Vec2 {
-    type Output = Vec2;
-    fn mul(self, rhs: f32) -> Vec2 {
-        vec2(self.x*rhs, self.y*rhs)
-    }
-}
-impl std::ops::Mul<Vec2> for f32 {
-    type Output = Vec2;
-    fn mul(self, rhs: Vec2) -> Vec2 {
-        vec2(self*rhs.x, self*rhs.y)
-    }
-}
-impl std::ops::Mul<Vec2> for Vec2 {
-    type Output = Vec2;
-    fn mul(self, rhs: Vec2) -> Vec2 {
-        vec2(self.x*rhs.x, self.y*rhs.y)
-    }
-}
-impl std::ops::Div<f32> for Vec2 {
-    type Output = Vec2;
-    fn div(self, rhs: f32) -> Vec2 {
-        vec2(self.x/rhs,self.y/rhs)
-    }
-}
-impl std::ops::Div<Vec2> for f32 {
-    type Output = Vec2;
-    fn div(self, rhs: Vec2) -> Vec2 {
-        vec2(self/rhs.x,self/rhs.y)
-    }
-}
-impl std::ops::Div<Vec2> for Vec2 {
-    type Output = Vec2;
-    fn div(self, rhs: Vec2) -> Vec2 {
-        vec2(self.x/rhs.x,self.y/rhs.y)
-    }
-}
-impl std::ops::Rem<f32> for Vec2 {
-    type Output = Vec2;
-    fn rem(self, rhs: f32) -> Self::Output {
-        vec2(self.x % rhs, self.y % rhs)
-    }
-}
-impl std::ops::Rem<Vec2> for Vec2 {
-    type Output = Vec2;
 
-    fn rem(self, rhs: Vec2) -> Self::Output {
-        vec2(self.x % rhs.x, self.y % rhs.y)
-    }
+#[test]
+fn test_vec2_rotation() {
+    use core::f32::consts::{FRAC_PI_2, PI};
+    let v = vec2(2.0, 0.0);
+    assert_eq!(v.perp(), vec2(0.0, 2.0));
+    assert!((v.rotate(FRAC_PI_2) - vec2(0.0, 2.0)).norm() < 1e-6);
+    assert!((v.rotate(PI) - vec2(-2.0, 0.0)).norm() < 1e-6);
+    assert!((Vec2::from_angle(FRAC_PI_2) - vec2(0.0, 1.0)).norm() < 1e-6);
+    assert!((vec2(-1.0, -1.0).to_angle() + 0.75 * PI).abs() < 1e-6);
+    assert!((vec2(1.0, 0.0).signed_angle(vec2(0.0, -3.0)) + FRAC_PI_2).abs() < 1e-6);
+    assert!((vec2(1.0, 0.0).angle_between(vec2(0.0, -3.0)) - FRAC_PI_2).abs() < 1e-6);
+    let a = vec2(0.3, -1.2);
+    assert!((a.rotate(a.signed_angle(vec2(1.0, 1.0))).unit().unwrap() - vec2(1.0, 1.0).unit().unwrap()).norm() < 1e-5);
 }
-impl std::ops::AddAssign<Vec2> for Vec2 {
-    fn add_assign(&mut self, rhs: Vec2) {
-        *self = *self + rhs;
-    }
-}
-impl std::ops::SubAssign<Vec2> for Vec2 {
-    fn sub_assign(&mut self, rhs: Vec2) {
-        *self = *self - rhs;
-    }
-}
-impl std::ops::MulAssign<f32> for Vec2 {
-    fn mul_assign(&mut self, rhs: f32) {
-        *self = *self * rhs;
-    }
-}
-impl std::ops::MulAssign for Vec2 {
-    fn mul_assign(&mut self, rhs: Self) {
-        self.x *= rhs.x;
-        self.y *= rhs.y;
-    }
-}
-impl std::ops::DivAssign<f32> for Vec2 {
-    fn div_assign(&mut self, rhs: f32) {
-        *self = *self / rhs;
-    }
+
+#[test]
+fn test_vec2_refract() {
+    let i = vec2(1.0, -1.0).unit().unwrap();
+    let n = vec2(0.0, 1.0);
+    // same medium goes straight through
+    assert!((i.refract(n, 1.0).unwrap() - i).norm() < 1e-6);
+    // into denser medium bends toward the normal, snell: sin t = eta sin i
+    let t = i.refract(n, 1.0 / 1.5).unwrap();
+    assert!((t.x - i.x / 1.5).abs() < 1e-6);
+    assert!((t.norm() - 1.0).abs() < 1e-5);
+    // out of it at a shallow angle reflects totally
+    assert_eq!(i.refract(n, 1.5), None);
+    assert_eq!(i.reflect(n), vec2(i.x, -i.y));
 }
-impl std::ops::RemAssign<f32> for Vec2 {
-    fn rem_assign(&mut self, rhs: f32) {
-        *self = vec2(self.x % rhs, self.y % rhs);
-    }
-}
\ No newline at end of file
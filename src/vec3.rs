@@ -1,21 +1,17 @@
-use serde::{Serialize, Deserialize};
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize, Default)]
+#[cfg(all(feature = "libm", not(any(feature = "std", test))))]
+use crate::math::FloatMath;
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
 pub struct Vec3 {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
 pub const fn vec3(x: f32, y: f32, z: f32) -> Vec3 { Vec3 { x, y, z } }
+impl_vector!(Vec3 { x, y, z });
 impl Vec3 {
-    pub fn norm(&self) -> f32 { self.dot(*self).sqrt() }
-    pub fn dist(&self, other: Self) -> f32 { (*self - other).norm() }
-    pub fn lerp(&self, other: Self, t: f32) -> Self { *self * t + other * (1.0 - t) }
-    pub fn unit(&self) -> Option<Self> { let n = self.norm(); if n == 0.0 {None} else {Some(*self/n)}}
-    pub fn dot(&self, other: Self) -> f32 { self.x * other.x + self.y * other.y + self.z * other.z }
-    pub fn min(&self, other: Self) -> Self { vec3(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z)) }
-    pub fn max(&self, other: Self) -> Self { vec3(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z)) }
-    pub fn floor(&self) -> Self { vec3(self.x.floor(), self.y.floor(), self.z.floor()) }
-    pub fn ceil(&self) -> Self { vec3(self.x.ceil(), self.y.ceil(), self.z.ceil()) }
     pub fn cross(&self, other: Vec3) -> Vec3 {
         vec3(
             self.y * other.z - self.z * other.y,
@@ -23,152 +19,52 @@ impl Vec3 {
             self.x * other.y - self.y * other.x,
         )
     }
-}
-impl std::ops::Add<Vec3> for Vec3 {
-    type Output = Vec3;
-    fn add(self, rhs: Vec3) -> Vec3 {
-        Vec3 {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
-}
-impl std::ops::Sub<Vec3> for Vec3 {
-    type Output = Vec3;
-    fn sub(self, rhs: Vec3) -> Vec3 {
-        Vec3 {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-            z: self.z - rhs.z,
-        }
-    }
-}
-impl std::ops::Sub<f32> for Vec3 {
-    type Output = Vec3;
-    fn sub(self, rhs: f32) -> Vec3 {
-        Vec3 {
-            x: self.x - rhs,
-            y: self.y - rhs,
-            z: self.z - rhs,
-        }
-    }
-}
-impl std::ops::Sub<Vec3> for f32 {
-    type Output = Vec3;
-    fn sub(self, rhs: Vec3) -> Vec3 {
-        Vec3 {
-            x: self - rhs.x,
-            y: self - rhs.y,
-            z: self - rhs.z,
-        }
-    }
-}
-impl std::ops::Mul<f32> for Vec3 {
-    type Output = Vec3;
-    fn mul(self, rhs: f32) -> Vec3 {
-        Vec3 {
-            x: self.x * rhs,
-            y: self.y * rhs,
-            z: self.z * rhs,
-        }
-    }
-}
-impl std::ops::Mul<Vec3> for f32 {
-    type Output = Vec3;
-    fn mul(self, rhs: Vec3) -> Vec3 {
-        rhs * self
-    }
-}
-impl std::ops::Mul<Vec3> for Vec3 {
-    type Output = Vec3;
-    fn mul(self, rhs: Vec3) -> Vec3 {
-        Vec3 {
-            x: self.x * rhs.x,
-            y: self.y * rhs.y,
-            z: self.z * rhs.z,
-        }
-    }
-}
-impl std::ops::Div<f32> for Vec3 {
-    type Output = Vec3;
-    fn div(self, rhs: f32) -> Vec3 {
-        Vec3 {
-            x: self.x / rhs,
-            y: self.y / rhs,
-            z: self.z / rhs,
-        }
-    }
-}
-impl std::ops::Div<Vec3> for f32 {
-    type Output = Vec3;
-    fn div(self, rhs: Vec3) -> Vec3 {
-        Vec3 {
-            x: self / rhs.x,
-            y: self / rhs.y,
-            z: self / rhs.z,
-        }
-    }
-}
-impl std::ops::Div<Vec3> for Vec3 {
-    type Output = Vec3;
-    fn div(self, rhs: Vec3) -> Vec3 {
-        Vec3 {
-            x: self.x / rhs.x,
-            y: self.y / rhs.y,
-            z: self.z / rhs.z,
-        }
-    }
-}
-impl std::ops::AddAssign<Vec3> for Vec3 {
-    fn add_assign(&mut self, rhs: Vec3) {
-        *self = *self + rhs;
-    }
-}
-impl std::ops::SubAssign<Vec3> for Vec3 {
-    fn sub_assign(&mut self, rhs: Vec3) {
-        *self = *self - rhs;
-    }
-}
-impl std::ops::MulAssign<f32> for Vec3 {
-    fn mul_assign(&mut self, rhs: f32) {
-        *self = *self * rhs;
-    }
-}
-impl std::ops::MulAssign<Vec3> for Vec3 {
-    fn mul_assign(&mut self, rhs: Vec3) {
-        self.x *= rhs.x;
-        self.y *= rhs.y;
-        self.z *= rhs.z;
-    }
-}
-impl std::ops::DivAssign<f32> for Vec3 {
-    fn div_assign(&mut self, rhs: f32) {
-        *self = *self / rhs;
-    }
-}
-impl std::ops::RemAssign<f32> for Vec3 {
-    fn rem_assign(&mut self, rhs: f32) {
-        *self = Vec3 {
-            x: self.x % rhs,
-            y: self.y % rhs,
-            z: self.z % rhs,
-        };
+    /// rotated angle radians anticlockwise about unit axis (rodrigues)
+    pub fn rotate_axis(&self, axis: Vec3, angle: f32) -> Vec3 {
+        let (s, c) = angle.sin_cos();
+        *self * c + axis.cross(*self) * s + axis * (axis.dot(*self) * (1.0 - c))
+    }
+    /// angle to rotate self by about axis to point along other (projected onto the plane of axis), in (-pi, pi]
+    pub fn signed_angle(&self, other: Vec3, axis: Vec3) -> f32 {
+        self.cross(other).dot(axis).atan2(self.dot(other))
+    }
+    /// two unit vectors making a right handed orthonormal basis with unit self (duff et al 2017)
+    pub fn orthonormal_basis(&self) -> (Vec3, Vec3) {
+        let sign = 1.0f32.copysign(self.z);
+        let a = -1.0 / (sign + self.z);
+        let b = self.x * self.y * a;
+        (
+            vec3(1.0 + sign * self.x * self.x * a, sign * b, -sign * self.x),
+            vec3(b, sign + self.y * self.y * a, -self.y),
+        )
     }
 }
-impl std::ops::RemAssign<Vec3> for Vec3 {
-    fn rem_assign(&mut self, rhs: Vec3) {
-        *self = Vec3 {
-            x: self.x % rhs.x,
-            y: self.y % rhs.y,
-            z: self.z % rhs.z,
-        };
+
+#[test]
+fn test_vec3_rotate_axis() {
+    use core::f32::consts::FRAC_PI_2;
+    let v = vec3(1.0, 0.0, 0.0);
+    assert!((v.rotate_axis(vec3(0.0, 0.0, 1.0), FRAC_PI_2) - vec3(0.0, 1.0, 0.0)).norm() < 1e-6);
+    assert!((v.rotate_axis(vec3(0.0, 1.0, 0.0), FRAC_PI_2) - vec3(0.0, 0.0, -1.0)).norm() < 1e-6);
+    // the axis component is left alone
+    let axis = vec3(1.0, 1.0, 1.0).unit().unwrap();
+    let r = vec3(1.0, 2.0, 3.0).rotate_axis(axis, 1.0);
+    assert!((r.dot(axis) - vec3(1.0, 2.0, 3.0).dot(axis)).abs() < 1e-5);
+    assert!((r.norm() - vec3(1.0, 2.0, 3.0).norm()).abs() < 1e-5);
+    let a = vec3(1.0, 0.0, 0.0);
+    let b = vec3(0.0, -1.0, 0.0);
+    assert!((a.signed_angle(b, vec3(0.0, 0.0, 1.0)) + FRAC_PI_2).abs() < 1e-6);
+    assert!((a.signed_angle(b, vec3(0.0, 0.0, -1.0)) - FRAC_PI_2).abs() < 1e-6);
+}
+
+#[test]
+fn test_vec3_orthonormal_basis() {
+    for n in [vec3(0.0, 0.0, 1.0), vec3(0.0, 0.0, -1.0), vec3(1.0, 2.0, 3.0), vec3(-0.2, 0.1, -5.0), vec3(1.0, 0.0, 0.0)] {
+        let n = n.unit().unwrap();
+        let (b1, b2) = n.orthonormal_basis();
+        assert!((b1.norm() - 1.0).abs() < 1e-5);
+        assert!((b2.norm() - 1.0).abs() < 1e-5);
+        assert!(b1.dot(n).abs() < 1e-5 && b2.dot(n).abs() < 1e-5 && b1.dot(b2).abs() < 1e-5);
+        assert!((b1.cross(b2) - n).norm() < 1e-5);
     }
 }
-impl std::fmt::Display for Vec3 {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let decimals = f.precision().unwrap_or(2);
-        let string = format!("[{:.*}, {:.*}, {:.*}]", decimals, self.x, decimals, self.y, decimals, self.z);
-        f.pad_integral(true, "", &string)
-    }
-}
\ No newline at end of file
@@ -0,0 +1,43 @@
+use crate::*;
+use core::ops::{Add, Sub, Mul, Div};
+
+/// the bits of Vec2, Vec3 and Vec4 that dimension-generic code (paths etc) needs
+pub trait Vector: Copy + PartialEq + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self> + Div<f32, Output = Self> {
+    fn zero() -> Self;
+    fn dot(&self, other: Self) -> f32;
+    fn norm(&self) -> f32 { self.dot(*self).sqrt() }
+    /// some nonzero vector perpendicular to self (for nonzero self). for vec2 its the left hand perpendicular
+    fn orthogonal(&self) -> Self;
+}
+
+impl Vector for Vec2 {
+    fn zero() -> Self { vec2(0.0, 0.0) }
+    fn dot(&self, other: Self) -> f32 { Vec2::dot(self, other) }
+    fn orthogonal(&self) -> Self { vec2(-self.y, self.x) }
+}
+impl Vector for Vec3 {
+    fn zero() -> Self { vec3(0.0, 0.0, 0.0) }
+    fn dot(&self, other: Self) -> f32 { Vec3::dot(self, other) }
+    fn orthogonal(&self) -> Self {
+        // cross with whichever axis is least aligned
+        let a = if self.x.abs() < self.y.abs() && self.x.abs() < self.z.abs() {
+            vec3(1.0, 0.0, 0.0)
+        } else if self.y.abs() < self.z.abs() {
+            vec3(0.0, 1.0, 0.0)
+        } else {
+            vec3(0.0, 0.0, 1.0)
+        };
+        self.cross(a)
+    }
+}
+impl Vector for Vec4 {
+    fn zero() -> Self { vec4(0.0, 0.0, 0.0, 0.0) }
+    fn dot(&self, other: Self) -> f32 { Vec4::dot(self, other) }
+    fn orthogonal(&self) -> Self { vec4(-self.y, self.x, -self.w, self.z) }
+}
+
+/// unit vector in the direction of v, or zero if v is zero
+pub(crate) fn normalize_or_zero<V: Vector>(v: V) -> V {
+    let n = v.norm();
+    if n == 0.0 { V::zero() } else { v / n }
+}